@@ -1,10 +1,22 @@
 use clap::{Args, Parser, Subcommand};
+use shared::app::action;
+use shared::app::autostart;
 use shared::app::config;
+use shared::app::doctor;
+use shared::app::export;
+use shared::app::import;
 use shared::app::install;
+use shared::app::recover;
 use shared::app::run;
 use shared::app::uninstall;
 use shared::app::update;
+use shared::app::run::session;
+use shared::domain::app::{
+    Autostart, CommandTemplate, LaunchOptions, NetworkOptions, SessionFlags, WindowRules,
+};
 use shared::infra::app_data;
+use shared::infra::browsers;
+use std::collections::BTreeMap;
 
 #[derive(Parser, Debug)]
 #[command(name = "tarantula", version, about = "Use web apps like desktop apps")]
@@ -13,21 +25,343 @@ struct Cli {
     command: Option<Commands>,
 
     run_cmd: Vec<String>,
+
+    /// Wait this many seconds before launching the app
+    #[arg(long = "delay")]
+    delay: Option<u32>,
+
+    /// Ask the browser to start the app minimized
+    #[arg(long = "minimized")]
+    minimized: bool,
+
+    /// Print the command the app would be launched with instead of launching it
+    #[arg(long = "print-command")]
+    print_command: bool,
 }
 
 #[derive(Debug, Subcommand)]
 enum Commands {
     Install(InstallArgs),
-    Uninstall(UpdateArgs),
+    Uninstall(UninstallArgs),
     List,
     Update(UpdateArgs),
     Config(ConfigArgs),
+    #[command(subcommand)]
+    Import(ImportCommands),
+    #[command(subcommand)]
+    Export(ExportCommands),
+    #[command(subcommand)]
+    Action(ActionCommands),
+    #[command(subcommand)]
+    Autostart(AutostartCommands),
+    /// Find and repair broken launchers, icons and browser settings
+    Doctor(DoctorArgs),
+    /// Rebuild the app list from the desktop entries in the applications directory
+    Recover,
+    /// List installed browsers and the one apps open in
+    Browsers,
+}
+
+#[derive(Debug, Args)]
+struct DoctorArgs {
+    /// Repair the problems that were found
+    #[arg(long = "fix")]
+    fix: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum AutostartCommands {
+    /// Launch the app when you log in
+    Enable(AutostartEnableArgs),
+    /// Stop launching the app when you log in
+    Disable(AutostartDisableArgs),
+}
+
+#[derive(Debug, Args)]
+struct AutostartEnableArgs {
+    name: String,
+
+    /// Seconds to wait after login before launching
+    #[arg(long = "delay")]
+    delay: Option<u32>,
+
+    /// Ask the browser to start the app minimized
+    #[arg(long = "minimized")]
+    minimized: bool,
+}
+
+#[derive(Debug, Args)]
+struct AutostartDisableArgs {
+    name: String,
+}
+
+#[derive(Debug, Subcommand)]
+enum ActionCommands {
+    /// Add a quick link to the app's launcher menu
+    Add(ActionAddArgs),
+    /// Remove a quick link from the app's launcher menu
+    Remove(ActionRemoveArgs),
+    /// List the app's quick links
+    List(ActionListArgs),
+}
+
+#[derive(Debug, Args)]
+struct ActionAddArgs {
+    name: String,
+    /// Label shown in the launcher, e.g. "My PRs"
+    label: String,
+    /// URL, or path in the app such as /pulls
+    target: String,
+}
+
+#[derive(Debug, Args)]
+struct ActionRemoveArgs {
+    name: String,
+    label: String,
+}
+
+#[derive(Debug, Args)]
+struct ActionListArgs {
+    name: String,
+}
+
+#[derive(Debug, Subcommand)]
+enum ImportCommands {
+    /// Import Omarchy web apps from the applications directory
+    Omarchy(OmarchyImportArgs),
+    /// Import web apps from Linux Mint's Web App Manager and Peppermint's ICE
+    #[command(alias = "ice")]
+    WebAppManager,
+    /// Install apps from a browser bookmark folder
+    Bookmarks(BookmarksImportArgs),
+}
+
+#[derive(Debug, Subcommand)]
+enum ExportCommands {
+    /// Write Omarchy web app launchers for all apps
+    Omarchy(OmarchyExportArgs),
+    /// Write all apps to a bookmark HTML file
+    Bookmarks(BookmarksExportArgs),
+    /// Keep Hyprland window rules and keybindings for your apps up to date
+    Hyprland(CompositorExportArgs),
+    /// Keep Sway window rules and keybindings for your apps up to date
+    Sway(CompositorExportArgs),
+}
+
+#[derive(Debug, Args)]
+struct CompositorExportArgs {
+    /// Stop updating the generated config and remove it
+    #[arg(long = "disable")]
+    disable: bool,
+}
+
+#[derive(Debug, Args)]
+struct OmarchyImportArgs {
+    /// Remove the Omarchy launchers once imported
+    #[arg(long = "remove")]
+    remove: bool,
+}
+
+#[derive(Debug, Args)]
+struct BookmarksImportArgs {
+    /// Browser to read bookmarks from (firefox, chrome, chromium, brave, vivaldi, edge, ...)
+    #[arg(short = 'b', long = "browser", required_unless_present = "file")]
+    browser: Option<String>,
+
+    /// Bookmark HTML file to read instead of a browser profile
+    #[arg(long = "file", conflicts_with = "browser")]
+    file: Option<std::path::PathBuf>,
+
+    /// Bookmark folder, e.g. "Web Apps" or "Bookmarks bar/Web Apps"
+    #[arg(short = 'f', long = "folder", required_unless_present_any = ["tag", "file"])]
+    folder: Option<String>,
+
+    /// Firefox bookmark tag to read instead of a folder
+    #[arg(short = 't', long = "tag", conflicts_with = "folder")]
+    tag: Option<String>,
+
+    /// Browser profile to read instead of the default one
+    #[arg(short = 'p', long = "profile")]
+    profile: Option<String>,
+
+    /// Bookmarks to install, as listed numbers (e.g. 1,3) or "all"
+    #[arg(short = 's', long = "select")]
+    select: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct BookmarksExportArgs {
+    /// Bookmark HTML file to write
+    file: std::path::PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct OmarchyExportArgs {
+    /// Directory to write the launchers to (defaults to the applications directory)
+    #[arg(short = 'd', long = "dir")]
+    dir: Option<std::path::PathBuf>,
 }
 
 #[derive(Debug, Args)]
 struct InstallArgs {
     name: String,
     url: String,
+
+    /// Create a launcher command for the app in ~/.local/bin
+    #[arg(long = "shim")]
+    shim: bool,
+
+    /// Comma-separated submenus of the "Web Apps" menu to list the app in
+    #[arg(long = "tags", value_delimiter = ',')]
+    tags: Vec<String>,
+
+    /// Browser engine to run the app in: chromium, firefox, epiphany or embedded
+    #[arg(long = "engine")]
+    engine: Option<String>,
+
+    /// Run the browser in a sandbox: auto, bubblewrap or firejail
+    #[arg(long = "sandbox")]
+    sandbox: Option<String>,
+
+    #[command(flatten)]
+    entry: EntryArgs,
+
+    #[command(flatten)]
+    window: WindowArgs,
+
+    #[command(flatten)]
+    launch: LaunchArgs,
+
+    #[command(flatten)]
+    network: NetworkArgs,
+
+    #[command(flatten)]
+    command: CommandArgs,
+
+    #[command(flatten)]
+    session: SessionArgs,
+}
+
+#[derive(Debug, Args)]
+struct EntryArgs {
+    /// Description shown and searched by launchers, or "" to remove it
+    #[arg(long = "description")]
+    description: Option<String>,
+
+    /// Comma-separated search keywords
+    #[arg(long = "keywords", value_delimiter = ',')]
+    keywords: Option<Vec<String>>,
+
+    /// Comma-separated desktop entry categories, e.g. Network,Email
+    #[arg(long = "categories", value_delimiter = ',')]
+    categories: Option<Vec<String>>,
+
+    /// Comma-separated translated names as LOCALE=NAME, e.g. de=Post,fr=Courrier
+    #[arg(long = "localized-names", value_delimiter = ',')]
+    localized_names: Option<Vec<String>>,
+
+    /// Hide the app from launcher menus
+    #[arg(long = "hidden")]
+    hidden: Option<bool>,
+}
+
+#[derive(Debug, Args)]
+struct WindowArgs {
+    /// Workspace to open the app on (Hyprland and Sway)
+    #[arg(long = "workspace")]
+    workspace: Option<String>,
+
+    /// Open the app in a floating window (Hyprland and Sway)
+    #[arg(long = "float")]
+    float: Option<bool>,
+
+    /// Window size as WIDTHxHEIGHT, e.g. 1200x800 (Hyprland and Sway)
+    #[arg(long = "size")]
+    size: Option<String>,
+
+    /// Window opacity between 0 and 1 (Hyprland and Sway)
+    #[arg(long = "opacity")]
+    opacity: Option<f32>,
+
+    /// Key combination that launches the app, e.g. SUPER+SHIFT+G (Hyprland and Sway)
+    #[arg(long = "hotkey")]
+    hotkey: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct LaunchArgs {
+    /// Initial window size and position as WIDTHxHEIGHT or WIDTHxHEIGHT+X+Y, or "" to remove it
+    #[arg(long = "geometry")]
+    geometry: Option<String>,
+
+    /// Device scale factor, e.g. 1.25, or 1 to remove it
+    #[arg(long = "scale")]
+    scale: Option<f32>,
+
+    /// Language the app's pages are requested in, e.g. de-DE, or "" to remove it
+    #[arg(long = "language")]
+    language: Option<String>,
+
+    /// User agent sent to sites, or "" to remove it
+    #[arg(long = "user-agent")]
+    user_agent: Option<String>,
+
+    /// Open the app in a normal browser tab instead of an app window
+    #[arg(long = "tab")]
+    tab: Option<bool>,
+}
+
+#[derive(Debug, Args)]
+struct NetworkArgs {
+    /// Proxy server as SCHEME://HOST:PORT, e.g. socks5://localhost:1080, or "" to remove it
+    #[arg(long = "proxy")]
+    proxy: Option<String>,
+
+    /// Comma-separated hosts that don't go through the proxy, e.g. <local>,*.example.com
+    #[arg(long = "proxy-bypass", value_delimiter = ',')]
+    proxy_bypass: Option<Vec<String>>,
+
+    /// Host resolver rules, e.g. "MAP *.corp 10.0.0.53, EXCLUDE localhost", or "" to remove them
+    #[arg(long = "host-rules")]
+    host_rules: Option<String>,
+
+    /// DNS-over-HTTPS mode: off, automatic or secure, or "" to remove it
+    #[arg(long = "secure-dns")]
+    secure_dns: Option<String>,
+
+    /// DNS-over-HTTPS server template, e.g. https://dns.example.com/dns-query, or "" to remove it
+    #[arg(long = "doh-template")]
+    doh_template: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct CommandArgs {
+    /// Launch command template, e.g. "{wrapper} {browser} {engine_args} {extra_args}", or "" to remove it
+    #[arg(long = "launch-template")]
+    launch_template: Option<String>,
+
+    /// Command the browser runs in, e.g. prime-run, or "" to remove it
+    #[arg(long = "wrapper")]
+    wrapper: Option<String>,
+
+    /// Arguments passed to the browser after the engine's own, or "" to remove them
+    #[arg(long = "extra-args", allow_hyphen_values = true)]
+    extra_args: Option<String>,
+
+    /// Environment variable as NAME=VALUE, repeat for more; replaces the current ones, "" removes them
+    #[arg(long = "env")]
+    env: Option<Vec<String>>,
+}
+
+#[derive(Debug, Args)]
+struct SessionArgs {
+    /// Chromium flags on Wayland, e.g. "--ozone-platform-hint=auto --disable-gpu-compositing", or "default"
+    #[arg(long = "wayland-flags", allow_hyphen_values = true)]
+    wayland_flags: Option<String>,
+
+    /// Chromium flags on X11, or "default"
+    #[arg(long = "x11-flags", allow_hyphen_values = true)]
+    x11_flags: Option<String>,
 }
 
 #[derive(Debug, Args)]
@@ -44,12 +378,60 @@ struct UpdateArgs {
 
     #[arg(short = 'u', long = "url")]
     new_url: Option<String>,
+
+    /// Browser executable for this app, or "" to use the configured browser
+    #[arg(short = 'b', long = "browser")]
+    browser_path: Option<String>,
+
+    /// Run the app in its own browser profile
+    #[arg(long = "isolated")]
+    isolated: Option<bool>,
+
+    /// Keep a launcher command for the app in ~/.local/bin
+    #[arg(long = "shim")]
+    shim: Option<bool>,
+
+    /// Comma-separated submenus of the "Web Apps" menu, or "" to remove them
+    #[arg(long = "tags", value_delimiter = ',')]
+    tags: Option<Vec<String>>,
+
+    /// Browser engine to run the app in: chromium, firefox, epiphany, embedded, or "" for the browser's
+    #[arg(long = "engine")]
+    engine: Option<String>,
+
+    /// Run the browser in a sandbox: auto, bubblewrap or firejail, or "" to turn it off
+    #[arg(long = "sandbox")]
+    sandbox: Option<String>,
+
+    #[command(flatten)]
+    entry: EntryArgs,
+
+    #[command(flatten)]
+    window: WindowArgs,
+
+    #[command(flatten)]
+    launch: LaunchArgs,
+
+    #[command(flatten)]
+    network: NetworkArgs,
+
+    #[command(flatten)]
+    command: CommandArgs,
+
+    #[command(flatten)]
+    session: SessionArgs,
 }
 
 #[derive(Debug, Args)]
 struct ConfigArgs {
     #[arg(short = 'b', long = "browser")]
     browser_path: Option<String>,
+
+    #[command(flatten)]
+    command: CommandArgs,
+
+    #[command(flatten)]
+    session: SessionArgs,
 }
 
 pub fn run() -> Result<(), Box<dyn std::error::Error>> {
@@ -65,7 +447,98 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     match &cli.command {
         Some(Commands::Install(args)) => {
-            match install::install(args.name.as_str(), args.url.as_str(), &config) {
+            let size = match args.window.size.as_deref().map(str::parse).transpose() {
+                Ok(size) => size,
+                Err(e) => {
+                    eprint!("{}", e);
+                    return Ok(());
+                }
+            };
+            let localized_names = match parse_localized_names(&args.entry.localized_names) {
+                Ok(names) => names.unwrap_or_default(),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(());
+                }
+            };
+            let engine = match args.engine.as_deref().map(str::parse).transpose() {
+                Ok(engine) => engine,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(());
+                }
+            };
+            let geometry = match args.launch.geometry.as_deref().map(str::parse).transpose() {
+                Ok(geometry) => geometry,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(());
+                }
+            };
+            let secure_dns = match args.network.secure_dns.as_deref().map(str::parse).transpose() {
+                Ok(secure_dns) => secure_dns,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(());
+                }
+            };
+            let sandbox = match args.sandbox.as_deref().map(str::parse).transpose() {
+                Ok(sandbox) => sandbox,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(());
+                }
+            };
+            let command_template =
+                match apply_command_args(&args.command, CommandTemplate::default()) {
+                    Ok(command_template) => command_template,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        return Ok(());
+                    }
+                };
+            let session_flags = match apply_session_args(&args.session, SessionFlags::default()) {
+                Ok(session_flags) => session_flags,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(());
+                }
+            };
+            let options = install::InstallOptions {
+                shim: args.shim,
+                window: WindowRules {
+                    workspace: args.window.workspace.clone(),
+                    float: args.window.float.unwrap_or_default(),
+                    size,
+                    opacity: args.window.opacity,
+                },
+                hotkey: args.window.hotkey.clone(),
+                tags: args.tags.clone(),
+                description: args.entry.description.clone(),
+                keywords: args.entry.keywords.clone().unwrap_or_default(),
+                categories: args.entry.categories.clone().unwrap_or_default(),
+                localized_names,
+                hidden: args.entry.hidden.unwrap_or_default(),
+                engine,
+                launch: LaunchOptions {
+                    geometry,
+                    scale: args.launch.scale,
+                    language: args.launch.language.clone(),
+                    user_agent: args.launch.user_agent.clone(),
+                    tab: args.launch.tab.unwrap_or_default(),
+                },
+                network: NetworkOptions {
+                    proxy: args.network.proxy.clone(),
+                    proxy_bypass: args.network.proxy_bypass.clone().unwrap_or_default(),
+                    host_rules: args.network.host_rules.clone(),
+                    secure_dns,
+                    doh_template: args.network.doh_template.clone(),
+                },
+                command_template,
+                session_flags,
+                sandbox,
+            };
+            match install::install(args.name.as_str(), args.url.as_str(), &options, &config) {
                 Ok(_) => println!("Web app installed!"),
                 Err(install::InstallError::InvalidData(e)) => {
                     eprint!("{}\n", e);
@@ -89,19 +562,76 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
         Some(Commands::List) => {
             let apps = app_data::get_apps(&config).unwrap_or_else(|_| vec![]);
             for app in apps {
-                println!("{} - {}", app.name, app.url);
+                match &app.autostart {
+                    Some(autostart) => println!(
+                        "{} - {} ({})",
+                        app.name,
+                        app.url,
+                        autostart_label(autostart)
+                    ),
+                    None => println!("{} - {}", app.name, app.url),
+                }
             }
         }
         Some(Commands::Update(args)) => {
+            let localized_names = match parse_localized_names(&args.entry.localized_names) {
+                Ok(names) => names,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(());
+                }
+            };
+            let env = match parse_env(&args.command.env) {
+                Ok(env) => env,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(());
+                }
+            };
             let options = update::UpdateOptions {
                 name: args.new_name.clone(),
                 url: args.new_url.clone(),
+                browser_path: args.browser_path.clone(),
+                isolated: args.isolated,
+                shim: args.shim,
+                workspace: args.window.workspace.clone(),
+                float: args.window.float,
+                size: args.window.size.clone(),
+                opacity: args.window.opacity,
+                hotkey: args.window.hotkey.clone(),
+                tags: args.tags.clone(),
+                description: args.entry.description.clone(),
+                keywords: args.entry.keywords.clone(),
+                categories: args.entry.categories.clone(),
+                localized_names,
+                hidden: args.entry.hidden,
+                engine: args.engine.clone(),
+                geometry: args.launch.geometry.clone(),
+                scale: args.launch.scale,
+                language: args.launch.language.clone(),
+                user_agent: args.launch.user_agent.clone(),
+                tab: args.launch.tab,
+                proxy: args.network.proxy.clone(),
+                proxy_bypass: args.network.proxy_bypass.clone(),
+                host_rules: args.network.host_rules.clone(),
+                secure_dns: args.network.secure_dns.clone(),
+                doh_template: args.network.doh_template.clone(),
+                launch_template: args.command.launch_template.clone(),
+                wrapper: args.command.wrapper.clone(),
+                extra_args: args.command.extra_args.clone(),
+                env,
+                wayland_flags: args.session.wayland_flags.clone(),
+                x11_flags: args.session.x11_flags.clone(),
+                sandbox: args.sandbox.clone(),
             };
             match update::update(args.name.as_str(), &options, &config) {
                 Ok(_) => println!("App updated!"),
                 Err(update::UpdateError::AppNotFound) => {
                     eprint!("App not found: {}", args.name);
                 }
+                Err(update::UpdateError::InvalidData(e)) => {
+                    eprint!("{}", e);
+                }
                 Err(update::UpdateError::Io(e)) => {
                     eprint!("Error updating app: {}", e);
                 }
@@ -125,43 +655,268 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
                 }
                 return Ok(());
             }
-        }
-        None => {
-            if cli.run_cmd.is_empty() {
-                println!("Run ui");
 
-                // Get embedded UI binary using relative path from build.rs
-                const UI_BINARY: &[u8] = include_bytes!("../../ui-binary");
+            let command_template =
+                match apply_command_args(&args.command, config.command_template.clone()) {
+                    Ok(command_template) => command_template,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        return Ok(());
+                    }
+                };
+            if command_template != config.command_template {
+                match config::update_command_template(command_template, &mut config) {
+                    Ok(_) => println!("Launch command updated!"),
+                    Err(config::ConfigError::InvalidTemplate(e)) => eprintln!("{}", e),
+                    Err(e) => eprintln!("Error updating the launch command: {:?}", e),
+                }
+            }
 
-                // Create temp file or use a cached location
-                let cache_dir = dirs::cache_dir()
-                    .ok_or("Could not find cache directory")?
-                    .join("tarantula");
-                std::fs::create_dir_all(&cache_dir)?;
+            let session_flags =
+                match apply_session_args(&args.session, config.session_flags.clone()) {
+                    Ok(session_flags) => session_flags,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        return Ok(());
+                    }
+                };
+            if session_flags != config.session_flags {
+                match config::update_session_flags(session_flags, &mut config) {
+                    Ok(_) => println!("Session flags updated!"),
+                    Err(e) => eprintln!("Error updating the session flags: {:?}", e),
+                }
+            }
+        }
+        Some(Commands::Import(ImportCommands::Omarchy(args))) => {
+            let options = import::omarchy::ImportOptions {
+                remove_source: args.remove,
+            };
+            match import::omarchy::import(&options, &config) {
+                Ok(report) => print_import_report(&report),
+                Err(e) => eprint!("Error importing Omarchy web apps: {:?}", e),
+            }
+        }
+        Some(Commands::Import(ImportCommands::WebAppManager)) => {
+            match import::web_app_manager::import(&config) {
+                Ok(report) => print_import_report(&report),
+                Err(e) => eprint!("Error importing web apps: {:?}", e),
+            }
+        }
+        Some(Commands::Import(ImportCommands::Bookmarks(args))) => {
+            let options = import::bookmarks::ReadOptions {
+                browser: args.browser.clone(),
+                profile: args.profile.clone(),
+                file: args.file.clone(),
+                folder: args.folder.clone(),
+                tag: args.tag.clone(),
+            };
+            let bookmarks = match import::bookmarks::read(&options) {
+                Ok(bookmarks) => bookmarks,
+                Err(e) => {
+                    eprint!("Error reading bookmarks: {:?}", e);
+                    return Ok(());
+                }
+            };
 
-                let ui_path = cache_dir.join("ui");
+            let selection = match &args.select {
+                Some(select) => select,
+                None => {
+                    for (index, bookmark) in bookmarks.iter().enumerate() {
+                        println!("{}. {} - {}", index + 1, bookmark.name, bookmark.url);
+                    }
+                    if bookmarks.is_empty() {
+                        println!("No web bookmarks found.");
+                    } else {
+                        println!("Run again with --select <numbers|all> to install them.");
+                    }
+                    return Ok(());
+                }
+            };
 
-                // Write binary if it doesn't exist or is outdated
-                if !ui_path.exists() || std::fs::metadata(&ui_path)?.len() != UI_BINARY.len() as u64
-                {
-                    std::fs::write(&ui_path, UI_BINARY)?;
+            let selected = match select_bookmarks(&bookmarks, selection) {
+                Ok(selected) => selected,
+                Err(e) => {
+                    eprint!("{}", e);
+                    return Ok(());
+                }
+            };
+            match import::bookmarks::install(&selected, &config) {
+                Ok(report) => print_import_report(&report),
+                Err(e) => eprint!("Error installing bookmarks: {:?}", e),
+            }
+        }
+        Some(Commands::Export(ExportCommands::Omarchy(args))) => {
+            let dir = args
+                .dir
+                .clone()
+                .unwrap_or_else(|| config.desktop_data_path.clone());
+            match export::omarchy::export(&dir, &config) {
+                Ok(report) => print_export_report(&report),
+                Err(e) => eprint!("Error exporting Omarchy web apps: {:?}", e),
+            }
+        }
+        Some(Commands::Export(ExportCommands::Bookmarks(args))) => {
+            match export::bookmarks::export(&args.file, &config) {
+                Ok(report) => {
+                    print_export_report(&report);
+                    println!(
+                        "Bookmarks written to {} in the \"{}\" folder.",
+                        args.file.display(),
+                        export::bookmarks::FOLDER_NAME
+                    );
+                }
+                Err(e) => eprint!("Error exporting bookmarks: {:?}", e),
+            }
+        }
+        Some(Commands::Export(ExportCommands::Hyprland(args))) => {
+            export_compositor(config::Compositor::Hyprland, args, &mut config);
+        }
+        Some(Commands::Export(ExportCommands::Sway(args))) => {
+            export_compositor(config::Compositor::Sway, args, &mut config);
+        }
+        Some(Commands::Action(ActionCommands::Add(args))) => {
+            match action::add(&args.name, &args.label, &args.target, &config) {
+                Ok(_) => println!("Action added!"),
+                Err(e) => print_action_error(&args.name, e),
+            }
+        }
+        Some(Commands::Action(ActionCommands::Remove(args))) => {
+            match action::remove(&args.name, &args.label, &config) {
+                Ok(_) => println!("Action removed!"),
+                Err(e) => print_action_error(&args.name, e),
+            }
+        }
+        Some(Commands::Action(ActionCommands::List(args))) => {
+            match action::list(&args.name, &config) {
+                Ok(actions) => {
+                    for action in actions {
+                        println!("{} - {}", action.label, action.target);
+                    }
+                }
+                Err(e) => print_action_error(&args.name, e),
+            }
+        }
+        Some(Commands::Autostart(AutostartCommands::Enable(args))) => {
+            let options = Autostart {
+                delay: args.delay,
+                minimized: args.minimized,
+            };
+            match autostart::enable(&args.name, options, &config) {
+                Ok(_) => println!("{} will start when you log in.", args.name),
+                Err(e) => print_autostart_error(&args.name, e),
+            }
+        }
+        Some(Commands::Autostart(AutostartCommands::Disable(args))) => {
+            match autostart::disable(&args.name, &config) {
+                Ok(_) => println!("{} will no longer start when you log in.", args.name),
+                Err(e) => print_autostart_error(&args.name, e),
+            }
+        }
+        Some(Commands::Doctor(args)) => {
+            let problems = match doctor::check(&config) {
+                Ok(problems) => problems,
+                Err(e) => {
+                    eprintln!("Error checking apps: {:?}", e);
+                    return Ok(());
+                }
+            };
+            if problems.is_empty() {
+                println!("No problems found.");
+                return Ok(());
+            }
 
-                    #[cfg(unix)]
-                    {
-                        use std::os::unix::fs::PermissionsExt;
-                        let mut perms = std::fs::metadata(&ui_path)?.permissions();
-                        perms.set_mode(0o755);
-                        std::fs::set_permissions(&ui_path, perms)?;
+            for problem in &problems {
+                println!("{}", problem);
+                if !args.fix {
+                    continue;
+                }
+                match doctor::fix(problem, &mut config) {
+                    Ok(fixed) => println!("  Fixed: {}", fixed),
+                    Err(doctor::DoctorError::CannotFix(reason)) => {
+                        println!("  Not fixed: {}", reason)
                     }
+                    Err(doctor::DoctorError::Io(e)) => println!("  Fixing failed: {}", e),
                 }
+            }
+            if !args.fix {
+                println!("Run `tarantula doctor --fix` to repair these problems.");
+            }
+        }
+        Some(Commands::Browsers) => {
+            let dirs = browsers::XdgDirs::from_env(&config);
+            let found = browsers::detect(&dirs);
+            for browser in &found {
+                let version = browsers::version(browser)
+                    .map(|v| format!(" {}", v))
+                    .unwrap_or_default();
+                let sandbox = match &browser.command.sandbox {
+                    Some(browsers::Sandbox::Flatpak(_)) => ", Flatpak",
+                    Some(browsers::Sandbox::Snap(_)) => ", Snap",
+                    None => "",
+                };
+                println!(
+                    "{}{} - {} ({:?}{}{})",
+                    if browser.default { "* " } else { "  " },
+                    browser.name,
+                    browser.command,
+                    browser.family,
+                    version,
+                    sandbox
+                );
+            }
+            if found.is_empty() {
+                println!("No browsers found.");
+            }
+            if found.iter().any(|b| b.default) {
+                println!("* default browser");
+            }
+
+            match (&config.browser_path, browsers::fallback(&dirs)) {
+                (Some(path), _) => println!("Apps open in the configured browser {}.", path),
+                (None, Some(browser)) => println!(
+                    "Apps open in {}, set another browser with `tarantula config --browser <path>`.",
+                    browser.name
+                ),
+                (None, None) => println!(
+                    "No Chromium- or Firefox-based browser found, apps open in tarantula's own window."
+                ),
+            }
+        }
+        Some(Commands::Recover) => match recover::recover(&config) {
+            Ok(report) => print_recover_report(&report),
+            Err(e) => eprintln!("Error recovering apps: {:?}", e),
+        },
+        None => {
+            if cli.run_cmd.is_empty() {
+                println!("Run ui");
 
-                std::process::Command::new(&ui_path)
+                std::process::Command::new(ui_binary()?)
                     .spawn()
                     .expect("Failed to launch UI");
             } else {
                 let name = &cli.run_cmd[0];
-                match run::run(name.as_str(), &config) {
-                    Ok(_) => println!("App launched!"),
+                // anything after the app name is a deep link into the app
+                let options = run::RunOptions {
+                    path: Some(cli.run_cmd[1..].join("/")).filter(|p| !p.is_empty()),
+                    delay: cli.delay,
+                    minimized: cli.minimized,
+                    // apps with the embedded engine open in the UI, which is only
+                    // written out for them
+                    ui_binary: Some(|| ui_binary().ok()),
+                };
+                let result = if cli.print_command {
+                    run::command_line(name.as_str(), &options, &config).map(|command| {
+                        // notes are shell comments, so the output still runs as a script
+                        for note in &command.notes {
+                            println!("# {}", note);
+                        }
+                        println!("{}", command);
+                    })
+                } else {
+                    run::run(name.as_str(), &options, &config).map(|_| println!("App launched!"))
+                };
+                match result {
+                    Ok(_) => {}
                     Err(run::RunError::AppNotFound(name)) => {
                         eprint!("App not found: {}", name);
                     }
@@ -178,3 +933,240 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+// Writes the UI binary embedded in the CLI to the cache directory and returns its path.
+fn ui_binary() -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+    // Get embedded UI binary using relative path from build.rs
+    const UI_BINARY: &[u8] = include_bytes!("../../ui-binary");
+
+    // Create temp file or use a cached location
+    let cache_dir = dirs::cache_dir()
+        .ok_or("Could not find cache directory")?
+        .join("tarantula");
+    std::fs::create_dir_all(&cache_dir)?;
+
+    let ui_path = cache_dir.join("ui");
+
+    // Write binary if it doesn't exist or is outdated
+    if !ui_path.exists() || std::fs::metadata(&ui_path)?.len() != UI_BINARY.len() as u64 {
+        std::fs::write(&ui_path, UI_BINARY)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&ui_path)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&ui_path, perms)?;
+        }
+    }
+
+    Ok(ui_path)
+}
+
+fn select_bookmarks(
+    bookmarks: &[import::bookmarks::Bookmark],
+    selection: &str,
+) -> Result<Vec<import::bookmarks::Bookmark>, String> {
+    if selection.trim() == "all" {
+        return Ok(bookmarks.to_vec());
+    }
+
+    selection
+        .split(',')
+        .map(|number| {
+            number
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|index| bookmarks.get(index))
+                .cloned()
+                .ok_or(format!("Invalid selection: {}", number.trim()))
+        })
+        .collect()
+}
+
+fn print_import_report(report: &import::ImportReport) {
+    for name in &report.imported {
+        println!("Imported {}", name);
+    }
+    for (name, reason) in &report.skipped {
+        println!("Skipped {}: {}", name, reason);
+    }
+    for (name, note) in &report.notes {
+        println!("Note for {}: {}", name, note);
+    }
+    if report.imported.is_empty() && report.skipped.is_empty() {
+        println!("No web apps found to import.");
+    }
+}
+
+fn print_recover_report(report: &recover::RecoverReport) {
+    if let Some(backup) = &report.backup {
+        println!(
+            "The app list couldn't be read and was moved to {}",
+            backup.display()
+        );
+    }
+    for name in &report.recovered {
+        println!("Recovered {}", name);
+    }
+    for (name, conflict) in &report.conflicts {
+        println!("Conflict for {}: {}", name, conflict);
+    }
+    for (path, reason) in &report.skipped {
+        println!("Skipped {}: {}", path.display(), reason);
+    }
+    if report.recovered.is_empty() && report.conflicts.is_empty() && report.skipped.is_empty() {
+        println!("Nothing to recover, the app list matches the desktop entries.");
+    }
+}
+
+fn print_action_error(name: &str, error: action::ActionError) {
+    match error {
+        action::ActionError::AppNotFound => eprintln!("App not found: {}", name),
+        action::ActionError::InvalidData(e) => eprintln!("{}", e),
+        action::ActionError::Io(e) => eprintln!("Error updating actions: {}", e),
+    }
+}
+
+fn print_autostart_error(name: &str, error: autostart::AutostartError) {
+    match error {
+        autostart::AutostartError::AppNotFound => eprintln!("App not found: {}", name),
+        autostart::AutostartError::Io(e) => eprintln!("Error updating autostart: {}", e),
+    }
+}
+
+fn autostart_label(autostart: &Autostart) -> String {
+    let mut label = String::from("starts at login");
+    if let Some(delay) = autostart.delay {
+        label.push_str(&format!(" after {}s", delay));
+    }
+    if autostart.minimized {
+        label.push_str(", minimized");
+    }
+    label
+}
+
+fn print_export_report(report: &export::ExportReport) {
+    for name in &report.exported {
+        println!("Exported {}", name);
+    }
+    for (name, reason) in &report.skipped {
+        println!("Skipped {}: {}", name, reason);
+    }
+}
+
+fn export_compositor(
+    compositor: config::Compositor,
+    args: &CompositorExportArgs,
+    config: &mut config::Config,
+) {
+    if args.disable {
+        match export::compositor::disable(compositor, config) {
+            Ok(_) => println!("Stopped generating the {:?} config.", compositor),
+            Err(e) => eprint!("Error disabling the {:?} config: {:?}", compositor, e),
+        }
+        return;
+    }
+
+    match export::compositor::export(compositor, config) {
+        Ok(path) => {
+            println!("{:?} config written to {}.", compositor, path.display());
+            println!("It is regenerated whenever your apps change. Add this line to your config to use it:");
+            println!("  {}", export::compositor::include_line(compositor, config));
+        }
+        Err(e) => eprint!("Error exporting the {:?} config: {:?}", compositor, e),
+    }
+}
+
+// Applies the launch command flags to a template, empty values remove the setting.
+fn apply_command_args(
+    args: &CommandArgs,
+    mut command_template: CommandTemplate,
+) -> Result<CommandTemplate, String> {
+    if let Some(launch_template) = &args.launch_template {
+        command_template.template = Some(launch_template.clone()).filter(|t| !t.is_empty());
+    }
+    if let Some(wrapper) = &args.wrapper {
+        command_template.wrapper = Some(wrapper.clone()).filter(|w| !w.is_empty());
+    }
+    if let Some(extra_args) = &args.extra_args {
+        command_template.extra_args = Some(extra_args.clone()).filter(|a| !a.is_empty());
+    }
+    if let Some(env) = parse_env(&args.env)? {
+        command_template.env = env;
+    }
+    Ok(command_template)
+}
+
+fn apply_session_args(
+    args: &SessionArgs,
+    mut session_flags: SessionFlags,
+) -> Result<SessionFlags, String> {
+    if let Some(wayland_flags) = &args.wayland_flags {
+        session_flags.wayland = session::parse_flags(wayland_flags)?;
+    }
+    if let Some(x11_flags) = &args.x11_flags {
+        session_flags.x11 = session::parse_flags(x11_flags)?;
+    }
+    Ok(session_flags)
+}
+
+fn parse_env(values: &Option<Vec<String>>) -> Result<Option<BTreeMap<String, String>>, String> {
+    let values = match values {
+        Some(values) => values,
+        None => return Ok(None),
+    };
+
+    let mut env = BTreeMap::new();
+    for value in values.iter().filter(|v| !v.is_empty()) {
+        match value.split_once('=') {
+            Some((name, value)) if !name.is_empty() => {
+                env.insert(name.to_string(), value.to_string());
+            }
+            _ => return Err(format!("Expected NAME=VALUE, got {}", value)),
+        }
+    }
+
+    Ok(Some(env))
+}
+
+// Parses LOCALE=NAME pairs, an empty list clears the names
+fn parse_localized_names(
+    values: &Option<Vec<String>>,
+) -> Result<Option<BTreeMap<String, String>>, String> {
+    let values = match values {
+        Some(values) => values,
+        None => return Ok(None),
+    };
+
+    let mut names = BTreeMap::new();
+    for value in values.iter().filter(|v| !v.is_empty()) {
+        match value.split_once('=') {
+            Some((locale, name)) if !locale.is_empty() && !name.is_empty() => {
+                names.insert(locale.to_string(), name.to_string());
+            }
+            _ => return Err(format!("Expected LOCALE=NAME, got {}", value)),
+        }
+    }
+
+    Ok(Some(names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_subcommands_are_reserved_names() {
+        for subcommand in Cli::command().get_subcommands() {
+            assert!(
+                install::RESERVED_NAMES.contains(&subcommand.get_name()),
+                "{} isn't a reserved app name",
+                subcommand.get_name()
+            );
+        }
+    }
+}
@@ -1,5 +1,11 @@
+pub mod action;
+pub mod autostart;
 pub mod config;
+pub mod doctor;
+pub mod export;
+pub mod import;
 pub mod install;
+pub mod recover;
 pub mod run;
 pub mod uninstall;
 pub mod update;
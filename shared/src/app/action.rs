@@ -0,0 +1,149 @@
+use crate::app::config::Config;
+use crate::app::run;
+use crate::domain::app::{App, AppAction};
+use crate::infra::app_data;
+use crate::infra::desktop_data;
+
+#[derive(Debug)]
+pub enum ActionError {
+    AppNotFound,
+    InvalidData(String),
+    Io(std::io::Error),
+}
+
+pub fn list(app_name: &str, config: &Config) -> Result<Vec<AppAction>, ActionError> {
+    Ok(get_app(app_name, config)?.actions)
+}
+
+pub fn add(app_name: &str, label: &str, target: &str, config: &Config) -> Result<(), ActionError> {
+    let mut app = get_app(app_name, config)?;
+
+    let label = label.trim();
+    if label.is_empty() || label.chars().any(char::is_control) {
+        return Err(ActionError::InvalidData(format!(
+            "Invalid action label: {:?}",
+            label
+        )));
+    }
+    if find(&app, label).is_some() {
+        return Err(ActionError::InvalidData(format!(
+            "{} already has an action named {}",
+            app.name, label
+        )));
+    }
+    validate_target(&app, target)?;
+
+    app.actions.push(AppAction {
+        label: label.to_string(),
+        target: target.to_string(),
+    });
+
+    save(&app, config)
+}
+
+pub fn remove(app_name: &str, label: &str, config: &Config) -> Result<(), ActionError> {
+    let mut app = get_app(app_name, config)?;
+
+    let index = find(&app, label).ok_or_else(|| {
+        ActionError::InvalidData(format!("{} has no action named {}", app.name, label))
+    })?;
+    app.actions.remove(index);
+
+    save(&app, config)
+}
+
+fn get_app(app_name: &str, config: &Config) -> Result<App, ActionError> {
+    app_data::get_app(app_name, config)
+        .map_err(|e| ActionError::Io(std::io::Error::other(format!("{:?}", e))))?
+        .ok_or(ActionError::AppNotFound)
+}
+
+fn find(app: &App, label: &str) -> Option<usize> {
+    app.actions
+        .iter()
+        .position(|a| a.label.to_lowercase() == label.trim().to_lowercase())
+}
+
+// Targets are passed to `tarantula <app> <target>`, so they have to be URLs or
+// paths that resolve against the app's URL.
+fn validate_target(app: &App, target: &str) -> Result<(), ActionError> {
+    let is_path = target.starts_with('/') || target.starts_with('?') || target.starts_with('#');
+    if !is_path && !crate::utils::is_url(target) {
+        return Err(ActionError::InvalidData(format!(
+            "Action target must be a URL or a path starting with /, ? or #: {}",
+            target
+        )));
+    }
+
+    run::deep_link(&app.url, target)
+        .map(|_| ())
+        .map_err(|e| ActionError::InvalidData(format!("{:?}", e)))
+}
+
+fn save(app: &App, config: &Config) -> Result<(), ActionError> {
+    app_data::update_app(&app.name, app, config)
+        .map_err(|e| ActionError::Io(std::io::Error::other(format!("{:?}", e))))?;
+    desktop_data::update_entry(app, config)
+        .map_err(|e| ActionError::Io(std::io::Error::other(format!("{:?}", e))))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::install;
+    use crate::infra::desktop_entry::{self, DesktopFile, MAIN_GROUP};
+
+    #[test]
+    fn test_actions() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        install::install(
+            "GitHub",
+            "https://github.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+
+        add("GitHub", "My PRs", "/pulls", &config).unwrap();
+        add("GitHub", "Search", "/search?q=100%", &config).unwrap();
+        add("GitHub", "Status", "https://www.githubstatus.com", &config).unwrap();
+
+        assert!(matches!(
+            add("GitHub", "my prs", "/pulls", &config),
+            Err(ActionError::InvalidData(_))
+        ));
+        assert!(matches!(
+            add("GitHub", "Flag", "--no-sandbox", &config),
+            Err(ActionError::InvalidData(_))
+        ));
+        assert!(matches!(
+            add("GitLab", "Board", "/board", &config),
+            Err(ActionError::AppNotFound)
+        ));
+
+        let file = DesktopFile::read(&config.desktop_data_path.join("github.desktop")).unwrap();
+        assert_eq!(
+            file.group(MAIN_GROUP).unwrap().get_list("Actions"),
+            vec!["my-prs", "search", "status"]
+        );
+        let search = file.group("Desktop Action search").unwrap();
+        assert_eq!(search.get("Name"), Some("Search".to_string()));
+        assert_eq!(
+            desktop_entry::parse_exec(&search.get("Exec").unwrap()).unwrap(),
+            vec!["tarantula", "github", "/search?q=100%%"]
+        );
+
+        remove("GitHub", "my prs", &config).unwrap();
+        let labels: Vec<String> = list("GitHub", &config)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.label)
+            .collect();
+        assert_eq!(labels, vec!["Search", "Status"]);
+
+        let file = DesktopFile::read(&config.desktop_data_path.join("github.desktop")).unwrap();
+        assert!(file.group("Desktop Action my-prs").is_none());
+    }
+}
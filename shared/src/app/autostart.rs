@@ -0,0 +1,94 @@
+use crate::app::config::Config;
+use crate::domain::app::{App, Autostart};
+use crate::infra::app_data;
+use crate::infra::desktop_data;
+
+#[derive(Debug)]
+pub enum AutostartError {
+    AppNotFound,
+    Io(std::io::Error),
+}
+
+// Starts the app at login, replacing any earlier delay or minimized setting.
+pub fn enable(app_name: &str, autostart: Autostart, config: &Config) -> Result<(), AutostartError> {
+    let mut app = get_app(app_name, config)?;
+    app.autostart = Some(autostart);
+    save(&app, config)
+}
+
+pub fn disable(app_name: &str, config: &Config) -> Result<(), AutostartError> {
+    let mut app = get_app(app_name, config)?;
+    app.autostart = None;
+    save(&app, config)
+}
+
+fn get_app(app_name: &str, config: &Config) -> Result<App, AutostartError> {
+    app_data::get_app(app_name, config)
+        .map_err(|e| AutostartError::Io(std::io::Error::other(format!("{:?}", e))))?
+        .ok_or(AutostartError::AppNotFound)
+}
+
+fn save(app: &App, config: &Config) -> Result<(), AutostartError> {
+    app_data::update_app(&app.name, app, config)
+        .map_err(|e| AutostartError::Io(std::io::Error::other(format!("{:?}", e))))?;
+    desktop_data::update_autostart_entry(app, config)
+        .map_err(|e| AutostartError::Io(std::io::Error::other(format!("{:?}", e))))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{install, uninstall, update};
+    use crate::domain::app_name::AppName;
+
+    #[test]
+    fn test_autostart() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        install::install(
+            "Slack",
+            "https://app.slack.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+        let path =
+            |name: &str| desktop_data::autostart_path(&AppName::parse(name).unwrap(), &config);
+
+        let autostart = Autostart {
+            delay: Some(5),
+            minimized: false,
+        };
+        enable("Slack", autostart.clone(), &config).unwrap();
+        assert!(path("Slack").exists());
+        assert_eq!(
+            app_data::get_app("Slack", &config)
+                .unwrap()
+                .unwrap()
+                .autostart,
+            Some(autostart)
+        );
+
+        // the entry follows the app when it is renamed
+        let options = update::UpdateOptions {
+            name: Some("Work Chat".to_string()),
+            ..Default::default()
+        };
+        update::update("Slack", &options, &config).unwrap();
+        assert!(!path("Slack").exists());
+        assert!(path("Work Chat").exists());
+
+        disable("Work Chat", &config).unwrap();
+        assert!(!path("Work Chat").exists());
+
+        enable("Work Chat", Autostart::default(), &config).unwrap();
+        uninstall::uninstall("Work Chat", &config).unwrap();
+        assert!(!path("Work Chat").exists());
+
+        assert!(matches!(
+            enable("Discord", Autostart::default(), &config),
+            Err(AutostartError::AppNotFound)
+        ));
+    }
+}
@@ -1,5 +1,8 @@
 use std::path::PathBuf;
 
+use crate::app::run::template;
+use crate::domain::app::{CommandTemplate, SessionFlags};
+use crate::infra::browsers::BrowserCommand;
 use crate::infra::config_data;
 use crate::infra::config_data::ConfigDataError;
 use serde::{Deserialize, Serialize};
@@ -8,22 +11,70 @@ use serde::{Deserialize, Serialize};
 pub struct Config {
     pub app_data_path: PathBuf,
     pub desktop_data_path: PathBuf,
+    // where XDG menu directory files are written, usually ~/.local/share/desktop-directories
+    #[serde(default)]
+    pub desktop_directory_path: PathBuf,
     pub browser_path: Option<String>,
+    // where app launcher shims are written, usually ~/.local/bin
+    #[serde(default)]
+    pub bin_path: PathBuf,
+    // $XDG_CONFIG_HOME, usually ~/.config
+    #[serde(default)]
+    pub xdg_config_path: PathBuf,
+    // compositors whose generated config is kept up to date
+    #[serde(default)]
+    pub compositors: Vec<Compositor>,
+    // absolute path of the tarantula binary, for sessions that don't have it on their $PATH
+    #[serde(default)]
+    pub launcher_command: Option<String>,
+    // how apps' browser commands are wrapped, apps can override it
+    #[serde(default, skip_serializing_if = "CommandTemplate::is_empty")]
+    pub command_template: CommandTemplate,
+    // this machine's Chromium flags for Wayland and X11 sessions, apps can override them
+    #[serde(default, skip_serializing_if = "SessionFlags::is_empty")]
+    pub session_flags: SessionFlags,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compositor {
+    Hyprland,
+    Sway,
 }
 
 impl Config {
-    pub fn new(app_data_path: PathBuf, desktop_data_path: PathBuf) -> Self {
+    pub fn new(
+        app_data_path: PathBuf,
+        desktop_data_path: PathBuf,
+        bin_path: PathBuf,
+        xdg_config_path: PathBuf,
+    ) -> Self {
+        // desktop-directories sits next to the applications directory
+        let desktop_directory_path = desktop_data_path.with_file_name("desktop-directories");
         Self {
             app_data_path,
             desktop_data_path,
+            desktop_directory_path,
             browser_path: None,
+            bin_path,
+            xdg_config_path,
+            compositors: Vec::new(),
+            launcher_command: None,
+            command_template: CommandTemplate::default(),
+            session_flags: SessionFlags::default(),
         }
     }
+
+    // Command written into desktop entries, shims and compositor configs to launch apps.
+    pub fn launcher(&self) -> &str {
+        self.launcher_command.as_deref().unwrap_or("tarantula")
+    }
 }
 
 #[derive(Debug)]
 pub enum ConfigError {
     InvalidPath(String),
+    InvalidTemplate(String),
     JSON(serde_json::Error),
     Io(std::io::Error),
 }
@@ -39,7 +90,9 @@ pub fn create_config() -> Result<Config, ConfigError> {
         })?;
     let app_data_path = home_dir.join(".local/share/tarantula").to_path_buf();
     let desktop_data_path = home_dir.join(".local/share/applications").to_path_buf();
-    let mut config = Config::new(app_data_path, desktop_data_path);
+    let bin_path = home_dir.join(".local/bin");
+    let xdg_config_path = dirs::config_dir().unwrap_or(home_dir.join(".config"));
+    let mut config = Config::new(app_data_path, desktop_data_path, bin_path, xdg_config_path);
     config.browser_path = match get_browser_path(&config) {
         Ok(path) => path,
         Err(e) => {
@@ -47,6 +100,12 @@ pub fn create_config() -> Result<Config, ConfigError> {
             std::process::exit(1);
         }
     };
+    config.compositors = config_data::get_compositors(&config).map_err(ConfigError::Io)?;
+    config.launcher_command =
+        config_data::get_launcher_command(&config).map_err(ConfigError::Io)?;
+    config.command_template =
+        config_data::get_command_template(&config).map_err(ConfigError::Io)?;
+    config.session_flags = config_data::get_session_flags(&config).map_err(ConfigError::Io)?;
 
     Ok(config)
 }
@@ -57,7 +116,8 @@ pub fn update_browser_path(
 ) -> Result<(), ConfigError> {
     config.browser_path = Some(new_path.to_string());
 
-    if std::path::Path::new(new_path).exists() == false {
+    // a command line like `flatpak run com.brave.Browser` is accepted as well
+    if !BrowserCommand::parse(new_path).is_some_and(|c| c.exists()) {
         return Err(ConfigError::InvalidPath(
             "Executable path does not exist".to_string(),
         ));
@@ -70,6 +130,43 @@ pub fn update_browser_path(
     Ok(())
 }
 
+pub fn update_compositors(
+    compositors: Vec<Compositor>,
+    config: &mut Config,
+) -> Result<(), ConfigError> {
+    config_data::update_compositors(compositors, config).map_err(|e| match e {
+        ConfigDataError::Io(e) => ConfigError::Io(e),
+    })
+}
+
+pub fn update_launcher_command(
+    launcher_command: Option<String>,
+    config: &mut Config,
+) -> Result<(), ConfigError> {
+    config_data::update_launcher_command(launcher_command, config).map_err(|e| match e {
+        ConfigDataError::Io(e) => ConfigError::Io(e),
+    })
+}
+
+pub fn update_command_template(
+    command_template: CommandTemplate,
+    config: &mut Config,
+) -> Result<(), ConfigError> {
+    template::validate(&command_template).map_err(ConfigError::InvalidTemplate)?;
+    config_data::update_command_template(command_template, config).map_err(|e| match e {
+        ConfigDataError::Io(e) => ConfigError::Io(e),
+    })
+}
+
+pub fn update_session_flags(
+    session_flags: SessionFlags,
+    config: &mut Config,
+) -> Result<(), ConfigError> {
+    config_data::update_session_flags(session_flags, config).map_err(|e| match e {
+        ConfigDataError::Io(e) => ConfigError::Io(e),
+    })
+}
+
 pub fn get_browser_path(
     config: &crate::app::config::Config,
 ) -> Result<Option<String>, ConfigError> {
@@ -0,0 +1,403 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::app::config::{self, Config};
+use crate::app::export::compositor;
+use crate::domain::app::App;
+use crate::domain::app_name::AppName;
+use crate::infra::browsers::BrowserCommand;
+use crate::infra::desktop_data;
+use crate::infra::{app_data, icons, shim_data};
+use crate::utils;
+
+#[derive(Debug)]
+pub enum DoctorError {
+    Io(std::io::Error),
+    // the problem has to be fixed by hand, the message says how
+    CannotFix(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    // a desktop entry that launches an app that is no longer installed
+    OrphanedEntry(PathBuf),
+    OrphanedAutostartEntry(PathBuf),
+    MissingEntry(String),
+    MissingAutostartEntry(String),
+    // the entry launches a different command than the configured launcher
+    OutdatedEntry(String),
+    MissingIcon { app: String, icon: String },
+    // launchers run this command, but it can't be found on the session's $PATH
+    LauncherNotFound(String),
+    // app is None for the configured browser
+    BrowserNotFound { app: Option<String>, path: String },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::OrphanedEntry(path) => write!(
+                f,
+                "{} launches an app that is not installed, probably left behind by a rename or a failed uninstall",
+                path.display()
+            ),
+            Problem::OrphanedAutostartEntry(path) => write!(
+                f,
+                "{} starts an app at login that is not installed or has autostart turned off",
+                path.display()
+            ),
+            Problem::MissingEntry(app) => write!(
+                f,
+                "{} has no desktop entry, so it doesn't show up in launchers",
+                app
+            ),
+            Problem::MissingAutostartEntry(app) => write!(
+                f,
+                "{} should start at login, but its autostart entry is missing",
+                app
+            ),
+            Problem::OutdatedEntry(app) => write!(
+                f,
+                "The desktop entry of {} doesn't use the configured launcher command",
+                app
+            ),
+            Problem::MissingIcon { app, icon } => {
+                write!(f, "The icon of {} was deleted: {}", app, icon)
+            }
+            Problem::LauncherNotFound(command) => write!(
+                f,
+                "Launchers run `{}`, but it is not on your session's $PATH, so apps fail to start from menus",
+                command
+            ),
+            Problem::BrowserNotFound {
+                app: Some(app),
+                path,
+            } => {
+                write!(f, "The browser of {} doesn't exist: {}", app, path)
+            }
+            Problem::BrowserNotFound { app: None, path } => {
+                write!(f, "The configured browser doesn't exist: {}", path)
+            }
+        }
+    }
+}
+
+pub fn check(config: &Config) -> Result<Vec<Problem>, DoctorError> {
+    let apps = app_data::get_apps(config).map_err(io_error)?;
+    let mut problems = Vec::new();
+
+    let launcher = config.launcher();
+    let found = match session_path() {
+        Some(path) => utils::find_in(launcher, &path),
+        None => utils::find_in_path(launcher),
+    };
+    if found.is_none() {
+        problems.push(Problem::LauncherNotFound(launcher.to_string()));
+    }
+
+    if let Some(path) = &config.browser_path
+        && !BrowserCommand::parse(path).is_some_and(|c| c.exists())
+    {
+        problems.push(Problem::BrowserNotFound {
+            app: None,
+            path: path.clone(),
+        });
+    }
+
+    // apps with invalid names never get files, so there is nothing to check for them
+    let named: Vec<(&App, AppName)> = apps
+        .iter()
+        .filter_map(|app| Some((app, AppName::parse(&app.name).ok()?)))
+        .collect();
+
+    let entries = desktop_data::read_entries(&config.desktop_data_path).map_err(io_error)?;
+    for entry in &entries {
+        if let Some(slug) = desktop_data::launched_app(entry, config)
+            && !named.iter().any(|(_, name)| name.slug() == slug)
+        {
+            problems.push(Problem::OrphanedEntry(entry.path.clone()));
+        }
+    }
+
+    let autostart_dir = config.xdg_config_path.join("autostart");
+    for entry in desktop_data::read_entries(&autostart_dir).map_err(io_error)? {
+        let is_ours = file_name(&entry.path).starts_with("tarantula-");
+        let is_wanted = named.iter().any(|(app, name)| {
+            app.autostart.is_some() && desktop_data::autostart_path(name, config) == entry.path
+        });
+        if is_ours && !is_wanted {
+            problems.push(Problem::OrphanedAutostartEntry(entry.path));
+        }
+    }
+
+    for (app, name) in &named {
+        let path = desktop_data::entry_path(name, config);
+        match entries.iter().find(|e| e.path == path) {
+            None => problems.push(Problem::MissingEntry(app.name.clone())),
+            Some(entry) if entry.exec().first().map(String::as_str) != Some(launcher) => {
+                problems.push(Problem::OutdatedEntry(app.name.clone()))
+            }
+            Some(_) => {}
+        }
+
+        if app.autostart.is_some() && !desktop_data::autostart_path(name, config).exists() {
+            problems.push(Problem::MissingAutostartEntry(app.name.clone()));
+        }
+
+        // icon theme names are looked up by the launcher, only files can go missing
+        if let Some(icon) = &app.icon
+            && icon.contains('/')
+            && !Path::new(icon).exists()
+        {
+            problems.push(Problem::MissingIcon {
+                app: app.name.clone(),
+                icon: icon.clone(),
+            });
+        }
+
+        if let Some(path) = &app.browser_path
+            && !BrowserCommand::parse(path).is_some_and(|c| c.exists())
+        {
+            problems.push(Problem::BrowserNotFound {
+                app: Some(app.name.clone()),
+                path: path.clone(),
+            });
+        }
+    }
+
+    Ok(problems)
+}
+
+// Fixes the problem and returns a description of what was done.
+pub fn fix(problem: &Problem, config: &mut Config) -> Result<String, DoctorError> {
+    match problem {
+        Problem::OrphanedEntry(path) | Problem::OrphanedAutostartEntry(path) => {
+            std::fs::remove_file(path).map_err(DoctorError::Io)?;
+            Ok(format!("Removed {}", path.display()))
+        }
+        Problem::MissingEntry(name) | Problem::OutdatedEntry(name) => {
+            desktop_data::update_entry(&get_app(name, config)?, config).map_err(io_error)?;
+            Ok(format!("Rewrote the desktop entry of {}", name))
+        }
+        Problem::MissingAutostartEntry(name) => {
+            desktop_data::update_autostart_entry(&get_app(name, config)?, config)
+                .map_err(io_error)?;
+            Ok(format!("Rewrote the autostart entry of {}", name))
+        }
+        Problem::MissingIcon { app, .. } => {
+            let mut app = get_app(app, config)?;
+            // the icon is downloaded again, or dropped when the site has none
+            app.icon = icons::store_icon(&app, config).map_err(io_error)?;
+            let message = match app.icon {
+                Some(_) => format!("Downloaded the icon of {} again", app.name),
+                None => format!("Removed the icon of {}, none could be downloaded", app.name),
+            };
+            save(&app, config)?;
+            Ok(message)
+        }
+        Problem::LauncherNotFound(_) => {
+            let exe = std::env::current_exe().map_err(DoctorError::Io)?;
+            let exe = exe.to_str().ok_or_else(|| {
+                DoctorError::CannotFix(format!("{} is not a valid UTF-8 path", exe.display()))
+            })?;
+            config::update_launcher_command(Some(exe.to_string()), config).map_err(io_error)?;
+            rewrite_launchers(config)?;
+            Ok(format!("Launchers now run {}", exe))
+        }
+        Problem::BrowserNotFound { app: Some(app), .. } => {
+            let mut app = get_app(app, config)?;
+            app.browser_path = None;
+            save(&app, config)?;
+            Ok(format!("{} now uses the configured browser", app.name))
+        }
+        Problem::BrowserNotFound { app: None, .. } => Err(DoctorError::CannotFix(
+            "Set a browser with `tarantula config --browser <path>`".to_string(),
+        )),
+    }
+}
+
+// Rewrites everything that runs the launcher command.
+fn rewrite_launchers(config: &Config) -> Result<(), DoctorError> {
+    let apps = app_data::get_apps(config).map_err(io_error)?;
+    for app in apps.iter().filter(|a| AppName::parse(&a.name).is_ok()) {
+        desktop_data::update_entry(app, config).map_err(io_error)?;
+        desktop_data::update_autostart_entry(app, config).map_err(io_error)?;
+        if app.shim {
+            shim_data::create_shim(app, config).map_err(io_error)?;
+        }
+    }
+    compositor::sync(config).map_err(io_error)?;
+
+    Ok(())
+}
+
+// Menus and docks are started with the login session's $PATH, which can differ from the shell's.
+// systemd keeps it in the user manager's environment.
+fn session_path() -> Option<OsString> {
+    let output = std::process::Command::new("systemctl")
+        .args(["--user", "show-environment"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("PATH="))
+        .map(OsString::from)
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().and_then(|f| f.to_str()).unwrap_or("")
+}
+
+fn get_app(name: &str, config: &Config) -> Result<App, DoctorError> {
+    app_data::get_app(name, config)
+        .map_err(io_error)?
+        .ok_or_else(|| DoctorError::CannotFix(format!("{} is no longer installed", name)))
+}
+
+fn save(app: &App, config: &Config) -> Result<(), DoctorError> {
+    app_data::update_app(&app.name, app, config).map_err(io_error)?;
+    desktop_data::update_entry(app, config).map_err(io_error)?;
+    desktop_data::update_autostart_entry(app, config).map_err(io_error)?;
+
+    Ok(())
+}
+
+fn io_error(e: impl fmt::Debug) -> DoctorError {
+    DoctorError::Io(std::io::Error::other(format!("{:?}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::install;
+    use crate::domain::app::Autostart;
+
+    #[test]
+    fn test_check_and_fix() {
+        let (mut config, _dir, _desktop_dir) =
+            crate::test::create_test_config::create_test_config();
+        // a launcher that exists everywhere, so only the planted problems are reported
+        config.launcher_command = Some("/bin/sh".to_string());
+        install::install(
+            "Gmail",
+            "https://mail.google.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+        install::install(
+            "Slack",
+            "https://app.slack.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(check(&config).unwrap(), vec![]);
+
+        // a rename that left the old entry behind, a deleted entry and a deleted icon
+        let orphan = config.desktop_data_path.join("old_mail.desktop");
+        desktop_data::write_entry(
+            &orphan,
+            &[("Name", "Old Mail"), ("Exec", "tarantula old_mail")],
+        )
+        .unwrap();
+        let foreign = config.desktop_data_path.join("firefox.desktop");
+        desktop_data::write_entry(&foreign, &[("Name", "Firefox"), ("Exec", "firefox %u")])
+            .unwrap();
+        // entries the user wrote to run tarantula, which aren't orphans
+        let handwritten = [
+            ("news.desktop", "tarantula https://news.example.com"),
+            ("gmail-minimized.desktop", "tarantula --minimized gmail"),
+        ]
+        .map(|(file_name, exec)| {
+            let path = config.desktop_data_path.join(file_name);
+            desktop_data::write_entry(&path, &[("Name", "Mine"), ("Exec", exec)]).unwrap();
+            path
+        });
+        std::fs::remove_file(config.desktop_data_path.join("slack.desktop")).unwrap();
+        let mut gmail = app_data::get_app("Gmail", &config).unwrap().unwrap();
+        gmail.icon = Some(config.app_data_path.join("gone.ico").display().to_string());
+        gmail.browser_path = Some("/nonexistent/chromium".to_string());
+        gmail.autostart = Some(Autostart::default());
+        app_data::update_app("Gmail", &gmail, &config).unwrap();
+        let stale = config
+            .xdg_config_path
+            .join("autostart/tarantula-discord.desktop");
+        std::fs::create_dir_all(stale.parent().unwrap()).unwrap();
+        desktop_data::write_entry(&stale, &[("Exec", "tarantula discord")]).unwrap();
+
+        let problems = check(&config).unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                Problem::OrphanedEntry(orphan.clone()),
+                Problem::OrphanedAutostartEntry(stale.clone()),
+                Problem::MissingAutostartEntry("Gmail".to_string()),
+                Problem::MissingIcon {
+                    app: "Gmail".to_string(),
+                    icon: gmail.icon.clone().unwrap(),
+                },
+                Problem::BrowserNotFound {
+                    app: Some("Gmail".to_string()),
+                    path: "/nonexistent/chromium".to_string(),
+                },
+                Problem::MissingEntry("Slack".to_string()),
+            ]
+        );
+
+        // icons need the network, so that one is left alone
+        for problem in problems
+            .iter()
+            .filter(|p| !matches!(p, Problem::MissingIcon { .. }))
+        {
+            fix(problem, &mut config).unwrap();
+        }
+        assert!(!orphan.exists());
+        assert!(foreign.exists());
+        assert!(handwritten.iter().all(|path| path.exists()));
+        assert!(!stale.exists());
+        assert!(config.desktop_data_path.join("slack.desktop").exists());
+        assert_eq!(
+            check(&config).unwrap(),
+            vec![Problem::MissingIcon {
+                app: "Gmail".to_string(),
+                icon: gmail.icon.unwrap(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_outdated_entries() {
+        let (mut config, _dir, _desktop_dir) =
+            crate::test::create_test_config::create_test_config();
+        config.launcher_command = Some("/bin/sh".to_string());
+        install::install(
+            "Gmail",
+            "https://mail.google.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+
+        config.launcher_command = Some("/bin/true".to_string());
+        assert_eq!(
+            check(&config).unwrap(),
+            vec![Problem::OutdatedEntry("Gmail".to_string())]
+        );
+        fix(&Problem::OutdatedEntry("Gmail".to_string()), &mut config).unwrap();
+        assert_eq!(check(&config).unwrap(), vec![]);
+
+        assert!(matches!(
+            fix(
+                &Problem::BrowserNotFound {
+                    app: None,
+                    path: "/nonexistent".to_string()
+                },
+                &mut config
+            ),
+            Err(DoctorError::CannotFix(_))
+        ));
+    }
+}
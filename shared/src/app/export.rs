@@ -0,0 +1,20 @@
+pub mod bookmarks;
+pub mod compositor;
+pub mod omarchy;
+
+use crate::infra::app_data;
+use crate::infra::desktop_data;
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Desktop(desktop_data::DesktopDataError),
+    AppData(app_data::ProjectDataError),
+}
+
+#[derive(Debug, Default)]
+pub struct ExportReport {
+    pub exported: Vec<String>,
+    // app name and the reason it wasn't exported
+    pub skipped: Vec<(String, String)>,
+}
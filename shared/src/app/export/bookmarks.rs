@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use crate::app::config::Config;
+use crate::app::export::{ExportError, ExportReport};
+use crate::infra::app_data;
+
+pub const FOLDER_NAME: &str = "Web Apps";
+
+// Writes all apps into a "Web Apps" folder of a Netscape bookmark file,
+// which any browser can import and `tarantula import bookmarks --file` reads back.
+pub fn export(path: &Path, config: &Config) -> Result<ExportReport, ExportError> {
+    let apps = app_data::get_apps(config).map_err(ExportError::AppData)?;
+
+    let mut content = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n<!-- This is an automatically generated file.\n     It will be read and overwritten.\n     DO NOT EDIT! -->\n<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n<TITLE>Bookmarks</TITLE>\n<H1>Bookmarks</H1>\n<DL><p>\n",
+    );
+    content.push_str(&format!("    <DT><H3>{}</H3>\n    <DL><p>\n", FOLDER_NAME));
+
+    let mut report = ExportReport::default();
+    for app in apps {
+        content.push_str(&format!(
+            "        <DT><A HREF=\"{}\">{}</A>\n",
+            encode(&app.url),
+            encode(&app.name)
+        ));
+        report.exported.push(app.name);
+    }
+    content.push_str("    </DL><p>\n</DL><p>\n");
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(ExportError::Io)?;
+    }
+    std::fs::write(path, content).map_err(ExportError::Io)?;
+
+    Ok(report)
+}
+
+fn encode(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::import::bookmarks::{Bookmark, html};
+    use crate::domain::app::App;
+    use crate::test::create_test_config::create_test_config;
+
+    #[test]
+    fn test_round_trip() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        for (name, url) in [
+            ("Gmail", "https://mail.google.com/"),
+            ("R&D <Wiki>", "https://wiki.example.com/?a=1&b=2"),
+        ] {
+            app_data::add_app(
+                App {
+                    name: name.to_string(),
+                    url: url.to_string(),
+                    icon: None,
+                    ..Default::default()
+                },
+                &config,
+            )
+            .unwrap();
+        }
+
+        let path = config.app_data_path.join("bookmarks.html");
+        let report = export(&path, &config).unwrap();
+        assert_eq!(report.exported.len(), 2);
+
+        let bookmarks = html::read_folder(&path, FOLDER_NAME).unwrap();
+        assert_eq!(
+            bookmarks,
+            vec![
+                Bookmark {
+                    name: "Gmail".to_string(),
+                    url: "https://mail.google.com/".to_string()
+                },
+                Bookmark {
+                    name: "R&D <Wiki>".to_string(),
+                    url: "https://wiki.example.com/?a=1&b=2".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,379 @@
+use std::path::PathBuf;
+
+use crate::app::config::{self, Compositor, Config};
+use crate::app::export::ExportError;
+use crate::domain::app::{App, is_valid_workspace};
+use crate::domain::app_name::AppName;
+use crate::infra::app_data;
+use crate::utils;
+
+const HEADER: &str =
+    "# Generated by tarantula, do not edit.\n# Regenerated whenever your apps change.\n";
+
+// Writes the compositor's managed config file and keeps it up to date from now on.
+pub fn export(compositor: Compositor, config: &mut Config) -> Result<PathBuf, ExportError> {
+    if !config.compositors.contains(&compositor) {
+        let mut compositors = config.compositors.clone();
+        compositors.push(compositor);
+        config::update_compositors(compositors, config).map_err(|e| match e {
+            config::ConfigError::Io(e) => ExportError::Io(e),
+            e => ExportError::Io(std::io::Error::other(format!("{:?}", e))),
+        })?;
+    }
+
+    write(compositor, config)?;
+
+    Ok(config_path(compositor, config))
+}
+
+// Stops managing the compositor's config file and removes it.
+pub fn disable(compositor: Compositor, config: &mut Config) -> Result<(), ExportError> {
+    let compositors = config
+        .compositors
+        .iter()
+        .copied()
+        .filter(|c| *c != compositor)
+        .collect();
+    config::update_compositors(compositors, config).map_err(|e| match e {
+        config::ConfigError::Io(e) => ExportError::Io(e),
+        e => ExportError::Io(std::io::Error::other(format!("{:?}", e))),
+    })?;
+
+    match std::fs::remove_file(config_path(compositor, config)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(ExportError::Io(e)),
+        _ => Ok(()),
+    }
+}
+
+// Regenerates the config file of every managed compositor.
+pub fn sync(config: &Config) -> Result<(), ExportError> {
+    for compositor in &config.compositors {
+        write(*compositor, config)?;
+    }
+
+    Ok(())
+}
+
+pub fn config_path(compositor: Compositor, config: &Config) -> PathBuf {
+    match compositor {
+        Compositor::Hyprland => config.xdg_config_path.join("hypr/tarantula.conf"),
+        Compositor::Sway => config.xdg_config_path.join("sway/tarantula.conf"),
+    }
+}
+
+// The line that pulls the managed file into the compositor's main config.
+pub fn include_line(compositor: Compositor, config: &Config) -> String {
+    let path = config_path(compositor, config);
+    match compositor {
+        Compositor::Hyprland => format!("source = {}", path.display()),
+        Compositor::Sway => format!("include {}", path.display()),
+    }
+}
+
+fn write(compositor: Compositor, config: &Config) -> Result<(), ExportError> {
+    let apps = app_data::get_apps(config).map_err(ExportError::AppData)?;
+
+    let path = config_path(compositor, config);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(ExportError::Io)?;
+    }
+    std::fs::write(path, generate(compositor, &apps, config.launcher()))
+        .map_err(ExportError::Io)?;
+
+    Ok(())
+}
+
+pub fn generate(compositor: Compositor, apps: &[App], launcher: &str) -> String {
+    let mut content = String::from(HEADER);
+    for app in apps {
+        // a name that isn't safe in a config line gets no rules
+        let name = match AppName::parse(&app.name) {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        let lines = match compositor {
+            Compositor::Hyprland => hyprland_lines(app, &name, launcher),
+            Compositor::Sway => sway_lines(app, &name, launcher),
+        };
+        if lines.is_empty() {
+            continue;
+        }
+
+        content.push_str(&format!("\n# {}\n", name));
+        for line in lines {
+            content.push_str(&line);
+            content.push('\n');
+        }
+    }
+
+    content
+}
+
+fn hyprland_lines(app: &App, name: &AppName, launcher: &str) -> Vec<String> {
+    let class = format!("class:^({})$", regex::escape(&app.window_class()));
+    let window = &app.window;
+
+    let mut lines = Vec::new();
+    if let Some(workspace) = window
+        .workspace
+        .as_deref()
+        .filter(|w| is_valid_workspace(w))
+    {
+        lines.push(format!("windowrule = workspace {}, {}", workspace, class));
+    }
+    if window.float {
+        lines.push(format!("windowrule = float, {}", class));
+    }
+    if let Some(size) = &window.size {
+        lines.push(format!(
+            "windowrule = size {} {}, {}",
+            size.width, size.height, class
+        ));
+    }
+    if let Some(opacity) = window.opacity {
+        lines.push(format!("windowrule = opacity {}, {}", opacity, class));
+    }
+    if let Some(Ok((modifiers, key))) = app.hotkey.as_deref().map(parse_hotkey) {
+        let modifiers: Vec<&str> = modifiers.iter().map(|m| m.hyprland()).collect();
+        lines.push(format!(
+            "bind = {}, {}, exec, {}",
+            modifiers.join(" "),
+            key.to_uppercase(),
+            launch_command(launcher, name)
+        ));
+    }
+
+    lines
+}
+
+fn sway_lines(app: &App, name: &AppName, launcher: &str) -> Vec<String> {
+    let criteria = format!("[app_id=\"^{}$\"]", regex::escape(&app.window_class()));
+    let window = &app.window;
+
+    let mut lines = Vec::new();
+    if let Some(workspace) = window
+        .workspace
+        .as_deref()
+        .filter(|w| is_valid_workspace(w))
+    {
+        lines.push(format!("assign {} workspace {}", criteria, workspace));
+    }
+    if window.float {
+        lines.push(format!("for_window {} floating enable", criteria));
+    }
+    if let Some(size) = &window.size {
+        lines.push(format!(
+            "for_window {} resize set {} {}",
+            criteria, size.width, size.height
+        ));
+    }
+    if let Some(opacity) = window.opacity {
+        lines.push(format!("for_window {} opacity {}", criteria, opacity));
+    }
+    if let Some(Ok((modifiers, key))) = app.hotkey.as_deref().map(parse_hotkey) {
+        let mut keys: Vec<String> = modifiers.iter().map(|m| m.sway().to_string()).collect();
+        // sway matches letters by their lowercase keysym
+        keys.push(if key.chars().count() == 1 {
+            key.to_lowercase()
+        } else {
+            key
+        });
+        lines.push(format!(
+            "bindsym {} exec {}",
+            keys.join("+"),
+            launch_command(launcher, name)
+        ));
+    }
+
+    lines
+}
+
+fn launch_command(launcher: &str, name: &AppName) -> String {
+    format!("{} {}", utils::shell_quote(launcher), name.slug())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Modifier {
+    Super,
+    Shift,
+    Ctrl,
+    Alt,
+}
+
+impl Modifier {
+    fn hyprland(&self) -> &'static str {
+        match self {
+            Modifier::Super => "SUPER",
+            Modifier::Shift => "SHIFT",
+            Modifier::Ctrl => "CTRL",
+            Modifier::Alt => "ALT",
+        }
+    }
+
+    fn sway(&self) -> &'static str {
+        match self {
+            Modifier::Super => "Mod4",
+            Modifier::Shift => "Shift",
+            Modifier::Ctrl => "Control",
+            Modifier::Alt => "Mod1",
+        }
+    }
+}
+
+// Splits a hotkey such as SUPER+SHIFT+G into its modifiers and key.
+pub fn parse_hotkey(hotkey: &str) -> Result<(Vec<Modifier>, String), String> {
+    let mut parts: Vec<&str> = hotkey.split('+').map(|p| p.trim()).collect();
+    // keysyms like G, F1, Return or XF86AudioPlay, nothing that could end the bind
+    let key = parts
+        .pop()
+        .filter(|k| !k.is_empty() && k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        .ok_or(format!(
+            "Invalid hotkey {:?}, expected e.g. SUPER+SHIFT+G",
+            hotkey
+        ))?;
+
+    let modifiers = parts
+        .iter()
+        .map(|part| match part.to_lowercase().as_str() {
+            "super" | "mod4" | "win" | "meta" => Ok(Modifier::Super),
+            "shift" => Ok(Modifier::Shift),
+            "ctrl" | "control" => Ok(Modifier::Ctrl),
+            "alt" | "mod1" => Ok(Modifier::Alt),
+            _ => Err(format!("Unknown modifier {} in hotkey {}", part, hotkey)),
+        })
+        .collect::<Result<Vec<Modifier>, String>>()?;
+
+    Ok((modifiers, key.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::app::{WindowRules, WindowSize};
+    use crate::test::create_test_config::create_test_config;
+
+    fn gmail() -> App {
+        App {
+            name: "Gmail".to_string(),
+            url: "https://mail.google.com/".to_string(),
+            window: WindowRules {
+                workspace: Some("3".to_string()),
+                float: true,
+                size: Some(WindowSize {
+                    width: 1200,
+                    height: 800,
+                }),
+                opacity: Some(0.9),
+            },
+            hotkey: Some("SUPER+SHIFT+G".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_hyprland() {
+        let expected = format!(
+            "{}\n# Gmail\nwindowrule = workspace 3, class:^(tarantula\\-gmail)$\nwindowrule = float, class:^(tarantula\\-gmail)$\nwindowrule = size 1200 800, class:^(tarantula\\-gmail)$\nwindowrule = opacity 0.9, class:^(tarantula\\-gmail)$\nbind = SUPER SHIFT, G, exec, tarantula gmail\n",
+            HEADER
+        );
+        assert_eq!(
+            generate(Compositor::Hyprland, &[gmail()], "tarantula"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_generate_sway() {
+        let expected = format!(
+            "{}\n# Gmail\nassign [app_id=\"^tarantula\\-gmail$\"] workspace 3\nfor_window [app_id=\"^tarantula\\-gmail$\"] floating enable\nfor_window [app_id=\"^tarantula\\-gmail$\"] resize set 1200 800\nfor_window [app_id=\"^tarantula\\-gmail$\"] opacity 0.9\nbindsym Mod4+Shift+g exec tarantula gmail\n",
+            HEADER
+        );
+        assert_eq!(
+            generate(Compositor::Sway, &[gmail()], "tarantula"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_generate_skips_apps_without_rules() {
+        let app = App {
+            name: "Plain".to_string(),
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(generate(Compositor::Hyprland, &[app], "tarantula"), HEADER);
+    }
+
+    #[test]
+    fn test_parse_hotkey() {
+        assert_eq!(
+            parse_hotkey("super+Shift+Return").unwrap(),
+            (vec![Modifier::Super, Modifier::Shift], "Return".to_string())
+        );
+        assert!(parse_hotkey("HYPER+G").is_err());
+        assert!(parse_hotkey("SUPER+").is_err());
+    }
+
+    #[test]
+    fn test_attack_strings() {
+        for hotkey in [
+            "SUPER+G\nexec = sh -c reboot",
+            "SUPER+G, exec, reboot",
+            "SUPER+G exec reboot",
+            "SUPER+$(reboot)",
+            "SUPER+G;reboot",
+        ] {
+            assert!(parse_hotkey(hotkey).is_err(), "{:?}", hotkey);
+        }
+
+        for workspace in [
+            "1\nexec = sh -c reboot",
+            "1, class:^(.*)$",
+            "1 exec reboot",
+            "1;exec reboot",
+            "1\r",
+            "",
+        ] {
+            let window = WindowRules {
+                workspace: Some(workspace.to_string()),
+                ..Default::default()
+            };
+            assert!(window.validate().is_err(), "{:?}", workspace);
+
+            // rules saved before workspaces were checked aren't written either
+            let apps = [App { window, ..gmail() }];
+            for compositor in [Compositor::Hyprland, Compositor::Sway] {
+                let content = generate(compositor, &apps, "tarantula");
+                assert!(!content.contains("reboot"), "{}", content);
+                assert!(!content.contains(" workspace "), "{}", content);
+            }
+        }
+        for workspace in ["3", "name:mail", "special:scratch", "2:web", "mail_work-1"] {
+            let window = WindowRules {
+                workspace: Some(workspace.to_string()),
+                ..Default::default()
+            };
+            assert!(window.validate().is_ok(), "{:?}", workspace);
+        }
+    }
+
+    #[test]
+    fn test_export_and_sync() {
+        let (mut config, _app_dir, _desktop_dir) = create_test_config();
+        let path = export(Compositor::Sway, &mut config).unwrap();
+        assert_eq!(config.compositors, vec![Compositor::Sway]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), HEADER);
+
+        app_data::add_app(gmail(), &config).unwrap();
+        sync(&config).unwrap();
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("bindsym Mod4+Shift+g")
+        );
+
+        disable(Compositor::Sway, &mut config).unwrap();
+        assert!(config.compositors.is_empty());
+        assert!(!path.exists());
+    }
+}
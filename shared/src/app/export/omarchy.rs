@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use crate::app::config::Config;
+use crate::app::export::{ExportError, ExportReport};
+use crate::app::import::omarchy::LAUNCH_COMMAND;
+use crate::domain::app::App;
+use crate::domain::app_name::AppName;
+use crate::infra::app_data;
+use crate::infra::desktop_data;
+use crate::infra::desktop_entry;
+
+// Writes an Omarchy web app launcher for every app into `dir`.
+// Icons are copied to `dir/icons` the same way Omarchy stores them.
+pub fn export(dir: &Path, config: &Config) -> Result<ExportReport, ExportError> {
+    let apps = app_data::get_apps(config).map_err(ExportError::AppData)?;
+
+    std::fs::create_dir_all(dir).map_err(ExportError::Io)?;
+
+    let mut report = ExportReport::default();
+    for app in apps {
+        let name = match AppName::parse(&app.name) {
+            Ok(name) => name,
+            Err(e) => {
+                report.skipped.push((app.name.clone(), e.to_string()));
+                continue;
+            }
+        };
+        let path = dir.join(format!("{}.desktop", name));
+        if path == desktop_data::entry_path(&name, config) {
+            report.skipped.push((
+                app.name.clone(),
+                format!("{} is the app's Tarantula launcher", path.display()),
+            ));
+            continue;
+        }
+
+        let icon = export_icon(&app, dir)?;
+        let exec = desktop_entry::quote_exec(&[LAUNCH_COMMAND, &app.url]);
+        let mut fields = vec![
+            ("Version", "1.0"),
+            ("Name", app.name.as_str()),
+            ("Comment", app.name.as_str()),
+            ("Exec", exec.as_str()),
+            ("Terminal", "false"),
+            ("Type", "Application"),
+        ];
+        if let Some(icon) = icon.as_deref() {
+            fields.push(("Icon", icon));
+        }
+        fields.push(("StartupNotify", "true"));
+
+        desktop_data::write_entry(&path, &fields).map_err(ExportError::Desktop)?;
+        report.exported.push(app.name);
+    }
+
+    Ok(report)
+}
+
+fn export_icon(app: &App, dir: &Path) -> Result<Option<String>, ExportError> {
+    let icon = match &app.icon {
+        Some(icon) => icon,
+        None => return Ok(None),
+    };
+
+    // icon theme names can be used as-is
+    let source = Path::new(icon);
+    if !source.is_file() {
+        return Ok(Some(icon.clone()));
+    }
+
+    let icons_dir = dir.join("icons");
+    std::fs::create_dir_all(&icons_dir).map_err(ExportError::Io)?;
+
+    let mut file_name = app.name.clone();
+    if let Some(extension) = source.extension().and_then(|e| e.to_str()) {
+        file_name.push('.');
+        file_name.push_str(extension);
+    }
+    let destination = icons_dir.join(file_name);
+    std::fs::copy(source, &destination).map_err(ExportError::Io)?;
+
+    Ok(Some(destination.to_string_lossy().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::import::omarchy::{ImportOptions, import};
+    use crate::test::create_test_config::create_test_config;
+
+    #[test]
+    fn test_export() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let icon_path = config.app_data_path.join("hey.ico");
+        std::fs::create_dir_all(&config.app_data_path).unwrap();
+        std::fs::write(&icon_path, b"icon").unwrap();
+        app_data::add_app(
+            App {
+                name: "HEY".to_string(),
+                url: "https://app.hey.com".to_string(),
+                icon: Some(icon_path.to_string_lossy().to_string()),
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+
+        let report = export(&config.desktop_data_path, &config).unwrap();
+        assert_eq!(report.exported, vec!["HEY".to_string()]);
+
+        let exported_icon = config.desktop_data_path.join("icons/HEY.ico");
+        assert!(exported_icon.exists());
+
+        let content =
+            std::fs::read_to_string(config.desktop_data_path.join("HEY.desktop")).unwrap();
+        let expected = format!(
+            "[Desktop Entry]\nVersion=1.0\nName=HEY\nComment=HEY\nExec=omarchy-launch-webapp https://app.hey.com\nTerminal=false\nType=Application\nIcon={}\nStartupNotify=true\n",
+            exported_icon.display()
+        );
+        assert_eq!(content, expected);
+    }
+
+    #[test]
+    fn test_export_skips_tarantula_launcher() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        app_data::add_app(
+            App {
+                name: "gmail".to_string(),
+                url: "https://mail.google.com".to_string(),
+                icon: None,
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+
+        let report = export(&config.desktop_data_path, &config).unwrap();
+        assert!(report.exported.is_empty());
+        assert_eq!(report.skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let export_dir = tempfile::tempdir().unwrap();
+        app_data::add_app(
+            App {
+                name: "HEY".to_string(),
+                url: "https://app.hey.com".to_string(),
+                icon: None,
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+        export(export_dir.path(), &config).unwrap();
+
+        let (other_config, _other_app_dir, _other_desktop_dir) = create_test_config();
+        std::fs::copy(
+            export_dir.path().join("HEY.desktop"),
+            other_config.desktop_data_path.join("HEY.desktop"),
+        )
+        .unwrap();
+        let options = ImportOptions {
+            remove_source: false,
+        };
+        let report = import(&options, &other_config).unwrap();
+        assert_eq!(report.imported, vec!["HEY".to_string()]);
+    }
+}
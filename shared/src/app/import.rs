@@ -0,0 +1,50 @@
+pub mod bookmarks;
+pub mod omarchy;
+pub mod web_app_manager;
+
+use crate::app::config::Config;
+use crate::app::install::{self, InstallError};
+use crate::domain::app::App;
+use crate::infra::desktop_data;
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(std::io::Error),
+    JSON(serde_json::Error),
+    Sqlite(rusqlite::Error),
+    Desktop(desktop_data::DesktopDataError),
+    Install(InstallError),
+    NotFound(String),
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: Vec<String>,
+    // app name and the reason it wasn't imported
+    pub skipped: Vec<(String, String)>,
+    // app name and a setting that couldn't be carried over
+    pub notes: Vec<(String, String)>,
+}
+
+impl ImportReport {
+    // Adds the app to Tarantula, recording it as skipped if it can't be installed as-is.
+    fn add(&mut self, app: App, config: &Config) -> Result<bool, ImportError> {
+        let name = app.name.clone();
+        let result = install::add(app, config);
+        self.record(name, result)
+    }
+
+    fn record(&mut self, name: String, result: Result<(), InstallError>) -> Result<bool, ImportError> {
+        match result {
+            Ok(_) => {
+                self.imported.push(name);
+                Ok(true)
+            }
+            Err(InstallError::InvalidData(reason)) => {
+                self.skipped.push((name, reason));
+                Ok(false)
+            }
+            Err(e) => Err(ImportError::Install(e)),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+pub mod chromium;
+pub mod firefox;
+pub mod html;
+
+use std::path::PathBuf;
+
+use crate::app::config::Config;
+use crate::app::import::{ImportError, ImportReport};
+use crate::app::install;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub name: String,
+    pub url: String,
+}
+
+// Where to read bookmarks from: a browser profile or a bookmark HTML file,
+// and either a folder or a Firefox tag.
+pub struct ReadOptions {
+    pub browser: Option<String>,
+    pub profile: Option<String>,
+    pub file: Option<PathBuf>,
+    pub folder: Option<String>,
+    pub tag: Option<String>,
+}
+
+pub fn read(options: &ReadOptions) -> Result<Vec<Bookmark>, ImportError> {
+    let is_firefox = options.browser.as_deref() == Some("firefox");
+    if options.tag.is_some() && !is_firefox {
+        return Err(ImportError::NotFound(
+            "Bookmark tags are only supported for Firefox".to_string(),
+        ));
+    }
+
+    let folder = options.folder.as_deref();
+    match (&options.file, options.browser.as_deref()) {
+        (Some(file), _) => html::read_folder(file, folder.unwrap_or_default()),
+        (None, Some("firefox")) => {
+            let path = firefox::find_places_file(options.profile.as_deref())?;
+            match (folder, options.tag.as_deref()) {
+                (_, Some(tag)) => firefox::read_tag(&path, tag),
+                (Some(folder), None) => firefox::read_folder(&path, folder),
+                (None, None) => Err(ImportError::NotFound(
+                    "A bookmark folder or tag is required".to_string(),
+                )),
+            }
+        }
+        (None, Some(browser)) => {
+            let path = chromium::find_bookmarks_file(browser, options.profile.as_deref())?;
+            chromium::read_folder(&path, folder.unwrap_or_default())
+        }
+        (None, None) => Err(ImportError::NotFound(
+            "A browser or bookmark file is required".to_string(),
+        )),
+    }
+}
+
+// Installs the bookmarks as apps, fetching an icon for each one.
+pub fn install(bookmarks: &[Bookmark], config: &Config) -> Result<ImportReport, ImportError> {
+    let mut report = ImportReport::default();
+    for bookmark in bookmarks {
+        let result = install::install(
+            &bookmark.name,
+            &bookmark.url,
+            &install::InstallOptions::default(),
+            config,
+        );
+        report.record(bookmark.name.clone(), result)?;
+    }
+
+    Ok(report)
+}
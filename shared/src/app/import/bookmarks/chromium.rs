@@ -0,0 +1,240 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::app::import::ImportError;
+use crate::app::import::bookmarks::Bookmark;
+use crate::utils;
+
+// Browser names and their configuration directory, relative to ~/.config
+const BROWSERS: [(&str, &str); 8] = [
+    ("chrome", "google-chrome"),
+    ("chrome-beta", "google-chrome-beta"),
+    ("chromium", "chromium"),
+    ("brave", "BraveSoftware/Brave-Browser"),
+    ("vivaldi", "vivaldi"),
+    ("edge", "microsoft-edge"),
+    ("opera", "opera"),
+    ("thorium", "thorium"),
+];
+
+// Root folders of the Bookmarks file and the names browsers show for them
+const ROOTS: [(&str, &str); 3] = [
+    ("bookmark_bar", "Bookmarks bar"),
+    ("other", "Other bookmarks"),
+    ("synced", "Mobile bookmarks"),
+];
+
+pub fn browser_names() -> Vec<&'static str> {
+    BROWSERS.iter().map(|(name, _)| *name).collect()
+}
+
+// Finds the Bookmarks file of the browser's last used profile, or of `profile` if given.
+pub fn find_bookmarks_file(browser: &str, profile: Option<&str>) -> Result<PathBuf, ImportError> {
+    let config_dir = dirs::config_dir().ok_or(ImportError::NotFound(
+        "Could not find the config directory".to_string(),
+    ))?;
+
+    find_bookmarks_file_in(&config_dir, browser, profile)
+}
+
+fn find_bookmarks_file_in(
+    config_dir: &Path,
+    browser: &str,
+    profile: Option<&str>,
+) -> Result<PathBuf, ImportError> {
+    let browser_dir = match BROWSERS.iter().find(|(name, _)| *name == browser.to_lowercase()) {
+        Some((_, dir)) => config_dir.join(dir),
+        None => {
+            return Err(ImportError::NotFound(format!(
+                "Unknown browser {}, expected one of: {}",
+                browser,
+                browser_names().join(", ")
+            )));
+        }
+    };
+
+    let profile = match profile {
+        Some(profile) => profile.to_string(),
+        None => last_used_profile(&browser_dir).unwrap_or_else(|| "Default".to_string()),
+    };
+
+    // Opera keeps its profile directly in the browser directory
+    let candidates = [
+        browser_dir.join(&profile).join("Bookmarks"),
+        browser_dir.join("Bookmarks"),
+    ];
+    candidates
+        .into_iter()
+        .find(|path| path.exists())
+        .ok_or(ImportError::NotFound(format!(
+            "No bookmarks found for {} profile {} in {}",
+            browser,
+            profile,
+            browser_dir.display()
+        )))
+}
+
+fn last_used_profile(browser_dir: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(browser_dir.join("Local State")).ok()?;
+    let local_state: Value = serde_json::from_str(&contents).ok()?;
+    local_state["profile"]["last_used"]
+        .as_str()
+        .map(|p| p.to_string())
+}
+
+// Lists the web bookmarks directly inside `folder`, a "/" separated path that
+// may start with a root folder such as "Bookmarks bar".
+pub fn read_folder(path: &Path, folder: &str) -> Result<Vec<Bookmark>, ImportError> {
+    let contents = std::fs::read_to_string(path).map_err(ImportError::Io)?;
+    let bookmarks: Value = serde_json::from_str(&contents).map_err(ImportError::JSON)?;
+
+    let segments: Vec<&str> = folder
+        .split('/')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let node = ROOTS
+        .iter()
+        .find_map(|(key, display_name)| {
+            let root = &bookmarks["roots"][key];
+            match segments.first() {
+                Some(first) if matches_name(first, key) || matches_name(first, display_name) => {
+                    find_folder(root, &segments[1..])
+                }
+                _ => find_folder(root, &segments),
+            }
+        })
+        .ok_or(ImportError::NotFound(format!(
+            "Bookmark folder {} not found",
+            folder
+        )))?;
+
+    let children = node["children"].as_array().cloned().unwrap_or_default();
+    let bookmarks = children
+        .iter()
+        .filter(|child| child["type"] == "url")
+        .filter_map(|child| {
+            let url = child["url"].as_str()?;
+            if !utils::is_url(url) {
+                return None;
+            }
+            Some(Bookmark {
+                name: child["name"].as_str().unwrap_or(url).trim().to_string(),
+                url: url.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(bookmarks)
+}
+
+fn find_folder<'a>(node: &'a Value, segments: &[&str]) -> Option<&'a Value> {
+    let Some((first, rest)) = segments.split_first() else {
+        return Some(node);
+    };
+
+    node["children"]
+        .as_array()?
+        .iter()
+        .filter(|child| child["type"] == "folder")
+        .filter(|child| child["name"].as_str().is_some_and(|n| matches_name(first, n)))
+        .find_map(|child| find_folder(child, rest))
+}
+
+fn matches_name(segment: &str, name: &str) -> bool {
+    segment.eq_ignore_ascii_case(name.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOKMARKS: &str = r#"{
+        "checksum": "0",
+        "roots": {
+            "bookmark_bar": {
+                "children": [
+                    {
+                        "children": [
+                            { "name": "Gmail", "type": "url", "url": "https://mail.google.com/" },
+                            { "name": "Calendar", "type": "url", "url": "https://calendar.google.com/" },
+                            { "name": "Settings", "type": "url", "url": "chrome://settings" },
+                            { "children": [], "name": "Nested", "type": "folder" }
+                        ],
+                        "name": "Web Apps",
+                        "type": "folder"
+                    }
+                ],
+                "name": "Bookmarks bar",
+                "type": "folder"
+            },
+            "other": {
+                "children": [
+                    {
+                        "children": [
+                            { "name": "Jira", "type": "url", "url": "https://example.atlassian.net/" }
+                        ],
+                        "name": "Work",
+                        "type": "folder"
+                    }
+                ],
+                "name": "Other bookmarks",
+                "type": "folder"
+            },
+            "synced": { "children": [], "name": "Mobile bookmarks", "type": "folder" }
+        },
+        "version": 1
+    }"#;
+
+    fn write_bookmarks() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Bookmarks");
+        std::fs::write(&path, BOOKMARKS).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_read_folder() {
+        let (_dir, path) = write_bookmarks();
+
+        let bookmarks = read_folder(&path, "Web Apps").unwrap();
+        assert_eq!(
+            bookmarks,
+            vec![
+                Bookmark {
+                    name: "Gmail".to_string(),
+                    url: "https://mail.google.com/".to_string()
+                },
+                Bookmark {
+                    name: "Calendar".to_string(),
+                    url: "https://calendar.google.com/".to_string()
+                },
+            ]
+        );
+
+        assert_eq!(read_folder(&path, "Bookmarks bar/web apps").unwrap().len(), 2);
+        assert_eq!(read_folder(&path, "Other bookmarks/Work").unwrap().len(), 1);
+        assert!(read_folder(&path, "Missing").is_err());
+    }
+
+    #[test]
+    fn test_find_bookmarks_file() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let brave_dir = config_dir.path().join("BraveSoftware/Brave-Browser");
+        std::fs::create_dir_all(brave_dir.join("Profile 1")).unwrap();
+        std::fs::write(brave_dir.join("Profile 1/Bookmarks"), BOOKMARKS).unwrap();
+        std::fs::write(
+            brave_dir.join("Local State"),
+            r#"{ "profile": { "last_used": "Profile 1" } }"#,
+        )
+        .unwrap();
+
+        let path = find_bookmarks_file_in(config_dir.path(), "brave", None).unwrap();
+        assert_eq!(path, brave_dir.join("Profile 1/Bookmarks"));
+
+        assert!(find_bookmarks_file_in(config_dir.path(), "brave", Some("Default")).is_err());
+        assert!(find_bookmarks_file_in(config_dir.path(), "netscape", None).is_err());
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+
+use crate::app::import::ImportError;
+use crate::app::import::bookmarks::Bookmark;
+use crate::utils;
+
+const BOOKMARK_TYPE: i64 = 1;
+const FOLDER_TYPE: i64 = 2;
+
+// Root folders of places.sqlite and the names Firefox shows for them
+const ROOTS: [(&str, &str); 4] = [
+    ("toolbar_____", "Bookmarks Toolbar"),
+    ("menu________", "Bookmarks Menu"),
+    ("unfiled_____", "Other Bookmarks"),
+    ("mobile______", "Mobile Bookmarks"),
+];
+const TAGS_ROOT: &str = "tags________";
+
+// Finds places.sqlite of the default Firefox profile, or of `profile` if given.
+pub fn find_places_file(profile: Option<&str>) -> Result<PathBuf, ImportError> {
+    let home_dir = dirs::home_dir().ok_or(ImportError::NotFound(
+        "Could not find home directory".to_string(),
+    ))?;
+
+    find_places_file_in(&home_dir.join(".mozilla/firefox"), profile)
+}
+
+fn find_places_file_in(firefox_dir: &Path, profile: Option<&str>) -> Result<PathBuf, ImportError> {
+    let profiles_ini =
+        std::fs::read_to_string(firefox_dir.join("profiles.ini")).map_err(ImportError::Io)?;
+    let sections = parse_ini(&profiles_ini);
+
+    let profiles: Vec<&HashMap<String, String>> = sections
+        .iter()
+        .filter(|(name, _)| name.starts_with("Profile"))
+        .map(|(_, keys)| keys)
+        .collect();
+
+    let profile_path = |keys: &HashMap<String, String>| -> Option<PathBuf> {
+        let path = keys.get("Path")?;
+        if keys.get("IsRelative").map(|r| r.as_str()) == Some("0") {
+            Some(PathBuf::from(path))
+        } else {
+            Some(firefox_dir.join(path))
+        }
+    };
+
+    let path = match profile {
+        Some(profile) => profiles
+            .iter()
+            .find(|keys| {
+                keys.get("Name").map(|n| n.as_str()) == Some(profile)
+                    || keys.get("Path").is_some_and(|p| p.ends_with(profile))
+            })
+            .and_then(|keys| profile_path(keys)),
+        // the install section points at the profile Firefox actually opens
+        None => sections
+            .iter()
+            .find(|(name, _)| name.starts_with("Install"))
+            .and_then(|(_, keys)| keys.get("Default"))
+            .map(|path| firefox_dir.join(path))
+            .or_else(|| {
+                profiles
+                    .iter()
+                    .find(|keys| keys.get("Default").map(|d| d.as_str()) == Some("1"))
+                    .or(profiles.first())
+                    .and_then(|keys| profile_path(keys))
+            }),
+    };
+
+    path.map(|path| path.join("places.sqlite"))
+        .filter(|path| path.exists())
+        .ok_or(ImportError::NotFound(format!(
+            "No Firefox bookmarks found for profile {}",
+            profile.unwrap_or("default")
+        )))
+}
+
+fn parse_ini(contents: &str) -> Vec<(String, HashMap<String, String>)> {
+    let mut sections: Vec<(String, HashMap<String, String>)> = Vec::new();
+    for line in contents.lines().map(|l| l.trim()) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.to_string(), HashMap::new()));
+        } else if let (Some((key, value)), Some((_, keys))) =
+            (line.split_once('='), sections.last_mut())
+        {
+            keys.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    sections
+}
+
+// Lists the web bookmarks directly inside `folder`, a "/" separated path that
+// may start with a root folder such as "Bookmarks Toolbar".
+pub fn read_folder(path: &Path, folder: &str) -> Result<Vec<Bookmark>, ImportError> {
+    let (_copy, connection) = open(path)?;
+
+    let segments: Vec<&str> = folder
+        .split('/')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let mut folder_id = None;
+    for (guid, display_name) in ROOTS {
+        let root_id = match root_id(&connection, guid)? {
+            Some(id) => id,
+            None => continue,
+        };
+        folder_id = match segments.first() {
+            Some(first) if first.eq_ignore_ascii_case(display_name) => {
+                find_folder(&connection, root_id, &segments[1..])?
+            }
+            _ => find_folder(&connection, root_id, &segments)?,
+        };
+        if folder_id.is_some() {
+            break;
+        }
+    }
+    let folder_id = folder_id.ok_or(ImportError::NotFound(format!(
+        "Bookmark folder {} not found",
+        folder
+    )))?;
+
+    let mut statement = connection
+        .prepare(
+            "SELECT b.title, p.url FROM moz_bookmarks b JOIN moz_places p ON b.fk = p.id
+             WHERE b.parent = ?1 AND b.type = ?2 ORDER BY b.position",
+        )
+        .map_err(ImportError::Sqlite)?;
+    let rows = statement
+        .query_map((folder_id, BOOKMARK_TYPE), |row| {
+            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(ImportError::Sqlite)?;
+
+    to_bookmarks(rows)
+}
+
+// Lists the web bookmarks tagged with `tag`.
+pub fn read_tag(path: &Path, tag: &str) -> Result<Vec<Bookmark>, ImportError> {
+    let (_copy, connection) = open(path)?;
+
+    let tags_id = root_id(&connection, TAGS_ROOT)?.ok_or(ImportError::NotFound(
+        "No bookmark tags found".to_string(),
+    ))?;
+    let tag_id = find_folder(&connection, tags_id, &[tag])?.ok_or(ImportError::NotFound(
+        format!("Bookmark tag {} not found", tag),
+    ))?;
+
+    // tag entries have no title, so use the title of the bookmark itself
+    let mut statement = connection
+        .prepare(
+            "SELECT (SELECT title FROM moz_bookmarks WHERE fk = p.id AND title != '' LIMIT 1), p.url
+             FROM moz_bookmarks b JOIN moz_places p ON b.fk = p.id
+             WHERE b.parent = ?1 AND b.type = ?2 ORDER BY b.position",
+        )
+        .map_err(ImportError::Sqlite)?;
+    let rows = statement
+        .query_map((tag_id, BOOKMARK_TYPE), |row| {
+            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(ImportError::Sqlite)?;
+
+    to_bookmarks(rows)
+}
+
+// Firefox keeps places.sqlite locked while it runs, so read from a copy.
+fn open(path: &Path) -> Result<(tempfile::TempDir, Connection), ImportError> {
+    let copy = tempfile::tempdir().map_err(ImportError::Io)?;
+    for suffix in ["", "-wal"] {
+        let source = PathBuf::from(format!("{}{}", path.display(), suffix));
+        if source.exists() {
+            std::fs::copy(&source, copy.path().join(format!("places.sqlite{}", suffix)))
+                .map_err(ImportError::Io)?;
+        }
+    }
+
+    let connection = Connection::open_with_flags(
+        copy.path().join("places.sqlite"),
+        OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )
+    .map_err(ImportError::Sqlite)?;
+
+    Ok((copy, connection))
+}
+
+fn root_id(connection: &Connection, guid: &str) -> Result<Option<i64>, ImportError> {
+    connection
+        .query_row("SELECT id FROM moz_bookmarks WHERE guid = ?1", [guid], |row| {
+            row.get(0)
+        })
+        .optional()
+        .map_err(ImportError::Sqlite)
+}
+
+fn find_folder(
+    connection: &Connection,
+    parent: i64,
+    segments: &[&str],
+) -> Result<Option<i64>, ImportError> {
+    let Some((first, rest)) = segments.split_first() else {
+        return Ok(Some(parent));
+    };
+
+    let mut statement = connection
+        .prepare("SELECT id, title FROM moz_bookmarks WHERE parent = ?1 AND type = ?2")
+        .map_err(ImportError::Sqlite)?;
+    let children = statement
+        .query_map((parent, FOLDER_TYPE), |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?))
+        })
+        .map_err(ImportError::Sqlite)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ImportError::Sqlite)?;
+
+    for (id, title) in children {
+        if title.is_some_and(|t| t.trim().eq_ignore_ascii_case(first))
+            && let Some(id) = find_folder(connection, id, rest)?
+        {
+            return Ok(Some(id));
+        }
+    }
+
+    Ok(None)
+}
+
+fn to_bookmarks(
+    rows: impl Iterator<Item = rusqlite::Result<(Option<String>, String)>>,
+) -> Result<Vec<Bookmark>, ImportError> {
+    let mut bookmarks = Vec::new();
+    for row in rows {
+        let (title, url) = row.map_err(ImportError::Sqlite)?;
+        if !utils::is_url(&url) {
+            continue;
+        }
+        bookmarks.push(Bookmark {
+            name: title
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| url.clone()),
+            url,
+        });
+    }
+
+    Ok(bookmarks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_places(dir: &Path) -> PathBuf {
+        let path = dir.join("places.sqlite");
+        let connection = Connection::open(&path).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+                 CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER,
+                     parent INTEGER, position INTEGER, title TEXT, guid TEXT);
+                 INSERT INTO moz_places VALUES (1, 'https://mail.google.com/', 'Inbox'),
+                     (2, 'https://calendar.google.com/', 'Calendar'),
+                     (3, 'about:config', 'Config');
+                 INSERT INTO moz_bookmarks VALUES (1, 2, NULL, 0, 0, '', 'root________'),
+                     (2, 2, NULL, 1, 0, 'menu', 'menu________'),
+                     (3, 2, NULL, 1, 1, 'toolbar', 'toolbar_____'),
+                     (4, 2, NULL, 1, 2, 'tags', 'tags________'),
+                     (5, 2, NULL, 3, 0, 'Web Apps', 'webapps_____'),
+                     (6, 1, 1, 5, 0, 'Gmail', 'gmail_______'),
+                     (7, 1, 2, 5, 1, 'Calendar', 'calendar____'),
+                     (8, 1, 3, 5, 2, 'Config', 'config______'),
+                     (9, 2, NULL, 4, 0, 'webapp', 'tagwebapp___'),
+                     (10, 1, 1, 9, 0, NULL, 'taggmail____');",
+            )
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = create_places(dir.path());
+
+        let bookmarks = read_folder(&path, "Web Apps").unwrap();
+        assert_eq!(
+            bookmarks,
+            vec![
+                Bookmark {
+                    name: "Gmail".to_string(),
+                    url: "https://mail.google.com/".to_string()
+                },
+                Bookmark {
+                    name: "Calendar".to_string(),
+                    url: "https://calendar.google.com/".to_string()
+                },
+            ]
+        );
+
+        assert_eq!(
+            read_folder(&path, "Bookmarks Toolbar/Web Apps").unwrap().len(),
+            2
+        );
+        assert!(read_folder(&path, "Bookmarks Menu/Web Apps").is_err());
+    }
+
+    #[test]
+    fn test_read_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = create_places(dir.path());
+
+        let bookmarks = read_tag(&path, "webapp").unwrap();
+        assert_eq!(
+            bookmarks,
+            vec![Bookmark {
+                name: "Gmail".to_string(),
+                url: "https://mail.google.com/".to_string()
+            }]
+        );
+        assert!(read_tag(&path, "missing").is_err());
+    }
+
+    #[test]
+    fn test_find_places_file() {
+        let dir = tempfile::tempdir().unwrap();
+        for profile in ["abc.default", "def.default-release"] {
+            std::fs::create_dir_all(dir.path().join(profile)).unwrap();
+            std::fs::write(dir.path().join(profile).join("places.sqlite"), "").unwrap();
+        }
+        std::fs::write(
+            dir.path().join("profiles.ini"),
+            "[Profile1]\nName=default\nIsRelative=1\nPath=abc.default\nDefault=1\n\n[Profile0]\nName=default-release\nIsRelative=1\nPath=def.default-release\n\n[General]\nStartWithLastProfile=1\n\n[Install4F96D1932A9F858E]\nDefault=def.default-release\nLocked=1\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            find_places_file_in(dir.path(), None).unwrap(),
+            dir.path().join("def.default-release/places.sqlite")
+        );
+        assert_eq!(
+            find_places_file_in(dir.path(), Some("default")).unwrap(),
+            dir.path().join("abc.default/places.sqlite")
+        );
+        assert!(find_places_file_in(dir.path(), Some("work")).is_err());
+    }
+}
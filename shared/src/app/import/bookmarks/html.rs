@@ -0,0 +1,129 @@
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::app::import::ImportError;
+use crate::app::import::bookmarks::Bookmark;
+use crate::utils;
+
+// Lists the web bookmarks directly inside `folder` of a Netscape bookmark file,
+// the HTML format every browser imports and exports. Leading folders may be
+// left out of the path, so "Web Apps" matches "Bookmarks bar/Web Apps".
+pub fn read_folder(path: &Path, folder: &str) -> Result<Vec<Bookmark>, ImportError> {
+    let contents = std::fs::read_to_string(path).map_err(ImportError::Io)?;
+
+    let segments: Vec<String> = folder
+        .split('/')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let token_regex = Regex::new(
+        r#"(?is)<H3[^>]*>(.*?)</H3>|<A\s[^>]*?HREF="([^"]*)"[^>]*>(.*?)</A>|<DL>|</DL>"#,
+    )
+    .unwrap();
+
+    let mut path: Vec<String> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut found = segments.is_empty();
+    let mut bookmarks = Vec::new();
+    for token in token_regex.captures_iter(&contents) {
+        if let Some(name) = token.get(1) {
+            pending_folder = Some(decode(name.as_str()).trim().to_lowercase());
+        } else if let (Some(url), Some(name)) = (token.get(2), token.get(3)) {
+            if !path.ends_with(&segments) {
+                continue;
+            }
+            let url = decode(url.as_str());
+            if !utils::is_url(&url) {
+                continue;
+            }
+            let name = decode(name.as_str()).trim().to_string();
+            bookmarks.push(Bookmark {
+                name: if name.is_empty() { url.clone() } else { name },
+                url,
+            });
+        } else if token[0].eq_ignore_ascii_case("<DL>") {
+            // the top-level list has no heading
+            path.push(pending_folder.take().unwrap_or_default());
+            if path.len() > 1 && path.ends_with(&segments) {
+                found = true;
+            }
+        } else {
+            path.pop();
+        }
+    }
+
+    if !found {
+        return Err(ImportError::NotFound(format!(
+            "Bookmark folder {} not found",
+            folder
+        )));
+    }
+
+    Ok(bookmarks)
+}
+
+fn decode(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOKMARKS: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1700000000" LAST_MODIFIED="1700000000" PERSONAL_TOOLBAR_FOLDER="true">Bookmarks bar</H3>
+    <DL><p>
+        <DT><H3 ADD_DATE="1700000000">Web Apps</H3>
+        <DL><p>
+            <DT><A HREF="https://mail.google.com/" ADD_DATE="1700000000" ICON="data:image/png;base64,AAAA">Gmail</A>
+            <DT><A HREF="https://example.com/?a=1&amp;b=2" ADD_DATE="1700000000">Tom &amp; Jerry</A>
+            <DT><A HREF="javascript:alert(1)">Bookmarklet</A>
+            <DT><H3>Nested</H3>
+            <DL><p>
+                <DT><A HREF="https://nested.example.com/">Nested</A>
+            </DL><p>
+        </DL><p>
+        <DT><A HREF="https://news.example.com/">News</A>
+    </DL><p>
+</DL><p>
+"#;
+
+    #[test]
+    fn test_read_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bookmarks.html");
+        std::fs::write(&path, BOOKMARKS).unwrap();
+
+        let bookmarks = read_folder(&path, "Web Apps").unwrap();
+        assert_eq!(
+            bookmarks,
+            vec![
+                Bookmark {
+                    name: "Gmail".to_string(),
+                    url: "https://mail.google.com/".to_string()
+                },
+                Bookmark {
+                    name: "Tom & Jerry".to_string(),
+                    url: "https://example.com/?a=1&b=2".to_string()
+                },
+            ]
+        );
+
+        assert_eq!(read_folder(&path, "Bookmarks bar/Web Apps").unwrap().len(), 2);
+        assert_eq!(read_folder(&path, "bookmarks bar").unwrap().len(), 1);
+        assert!(read_folder(&path, "Missing").is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use crate::app::config::Config;
+use crate::app::import::{ImportError, ImportReport};
+use crate::domain::app::App;
+use crate::domain::app_name::AppName;
+use crate::infra::desktop_data::{self, DesktopEntry};
+use crate::utils;
+
+pub const LAUNCH_COMMAND: &str = "omarchy-launch-webapp";
+
+pub struct ImportOptions {
+    // delete the Omarchy launcher once the app has been imported
+    pub remove_source: bool,
+}
+
+pub fn import(options: &ImportOptions, config: &Config) -> Result<ImportReport, ImportError> {
+    let entries =
+        desktop_data::read_entries(&config.desktop_data_path).map_err(ImportError::Desktop)?;
+
+    let mut report = ImportReport::default();
+    for entry in entries {
+        let (name, url) = match (entry.get("Name"), web_app_url(&entry)) {
+            (Some(name), Some(url)) => (name.to_string(), url),
+            _ => continue,
+        };
+
+        let app = App {
+            name: name.clone(),
+            url,
+            icon: entry.get("Icon").map(|i| i.to_string()),
+            ..Default::default()
+        };
+
+        let imported = report.add(app, config)?;
+
+        // our own launcher replaces the Omarchy one when the file names match
+        let replaced = AppName::parse(&name)
+            .is_ok_and(|name| entry.path == desktop_data::entry_path(&name, config));
+        if imported && options.remove_source && !replaced {
+            std::fs::remove_file(&entry.path).map_err(ImportError::Io)?;
+        }
+    }
+
+    Ok(report)
+}
+
+fn web_app_url(entry: &DesktopEntry) -> Option<String> {
+    let exec = entry.exec();
+
+    let program = exec.first()?;
+    if Path::new(program).file_name()?.to_str()? != LAUNCH_COMMAND {
+        return None;
+    }
+
+    let url = exec.get(1)?;
+    if !utils::is_url(url) {
+        return None;
+    }
+
+    Some(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::app_data;
+    use crate::test::create_test_config::create_test_config;
+
+    fn write_omarchy_entry(config: &Config, name: &str, url: &str) {
+        let content = format!(
+            "[Desktop Entry]\nVersion=1.0\nName={}\nComment={}\nExec=omarchy-launch-webapp {}\nTerminal=false\nType=Application\nIcon=/icons/{}.png\nStartupNotify=true\n",
+            name, name, url, name
+        );
+        std::fs::write(
+            config.desktop_data_path.join(format!("{}.desktop", name)),
+            content,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_import() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        write_omarchy_entry(&config, "HEY", "https://app.hey.com");
+        std::fs::write(
+            config.desktop_data_path.join("firefox.desktop"),
+            "[Desktop Entry]\nName=Firefox\nExec=firefox %u\n",
+        )
+        .unwrap();
+
+        let options = ImportOptions {
+            remove_source: false,
+        };
+        let report = import(&options, &config).unwrap();
+        assert_eq!(report.imported, vec!["HEY".to_string()]);
+
+        let apps = app_data::get_apps(&config).unwrap();
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].url, "https://app.hey.com");
+        assert_eq!(apps[0].icon.as_deref(), Some("/icons/HEY.png"));
+
+        assert!(config.desktop_data_path.join("hey.desktop").exists());
+        assert!(config.desktop_data_path.join("HEY.desktop").exists());
+    }
+
+    #[test]
+    fn test_import_skips_installed_apps() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        write_omarchy_entry(&config, "HEY", "https://app.hey.com");
+
+        let options = ImportOptions {
+            remove_source: true,
+        };
+        import(&options, &config).unwrap();
+        assert!(!config.desktop_data_path.join("HEY.desktop").exists());
+
+        write_omarchy_entry(&config, "HEY", "https://app.hey.com");
+        let report = import(&options, &config).unwrap();
+        assert!(report.imported.is_empty());
+        assert_eq!(report.skipped.len(), 1);
+        assert!(config.desktop_data_path.join("HEY.desktop").exists());
+    }
+}
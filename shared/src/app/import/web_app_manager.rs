@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+
+use crate::app::config::Config;
+use crate::app::import::{ImportError, ImportReport};
+use crate::domain::app::{App, Engine};
+use crate::infra::browsers::{self, BrowserFamily};
+use crate::infra::desktop_data::{self, DesktopEntry};
+use crate::utils;
+
+// Browsers Web App Manager and ICE support that Tarantula has no engine for
+const UNSUPPORTED_BROWSERS: [&str; 1] = ["falkon"];
+
+// Imports the web apps created by Linux Mint's Web App Manager and Peppermint's ICE.
+pub fn import(config: &Config) -> Result<ImportReport, ImportError> {
+    let entries =
+        desktop_data::read_entries(&config.desktop_data_path).map_err(ImportError::Desktop)?;
+
+    let mut report = ImportReport::default();
+    for entry in entries {
+        let (name, url) = match (entry.get("Name"), entry.get("X-WebApp-URL")) {
+            (Some(name), Some(url)) if utils::is_url(url) => (name.to_string(), url.to_string()),
+            _ => continue,
+        };
+
+        let mut app = App {
+            name: name.clone(),
+            url,
+            icon: entry.get("Icon").map(|i| i.to_string()),
+            ..Default::default()
+        };
+        let notes = apply_settings(&entry, &mut app);
+
+        if report.add(app, config)? {
+            report
+                .notes
+                .extend(notes.into_iter().map(|note| (name.clone(), note)));
+        }
+    }
+
+    Ok(report)
+}
+
+// Carries the X-WebApp-* settings over to the app, returning the ones Tarantula can't honor.
+fn apply_settings(entry: &DesktopEntry, app: &mut App) -> Vec<String> {
+    let mut notes = Vec::new();
+    let exec = entry.exec();
+
+    let browser = entry.get("X-WebApp-Browser").unwrap_or_default();
+    let supported = !UNSUPPORTED_BROWSERS
+        .iter()
+        .any(|b| browser.to_lowercase().contains(b));
+
+    if !supported {
+        notes.push(format!(
+            "{} isn't supported, the app will use the default browser",
+            browser
+        ));
+    } else {
+        match browser_program(&exec) {
+            Some(path) => {
+                let engine = match browsers::family(&path.display().to_string()) {
+                    BrowserFamily::Firefox => Some(Engine::Firefox),
+                    BrowserFamily::Epiphany => Some(Engine::Epiphany),
+                    _ => None,
+                };
+                if engine.is_some() {
+                    app.engine = engine;
+                    if exec.iter().any(|arg| arg.contains("--profile")) {
+                        notes.push(format!(
+                            "the {} profile isn't carried over, the app starts with a new one",
+                            browser
+                        ));
+                    }
+                }
+                app.browser_path = Some(path.display().to_string());
+            }
+            None => notes.push(format!(
+                "could not find the {} executable, the app will use the default browser",
+                browser
+            )),
+        }
+    }
+
+    if entry.get("X-WebApp-Isolated") == Some("true") {
+        app.isolated = true;
+        if supported {
+            app.profile_path = exec
+                .iter()
+                .find_map(|arg| arg.strip_prefix("--user-data-dir="))
+                .map(|path| path.to_string());
+        }
+    }
+
+    if entry.get("X-WebApp-Navbar") == Some("true") {
+        notes.push("the navigation bar isn't supported".to_string());
+    }
+
+    if entry.get("X-WebApp-PrivateWindow") == Some("true") {
+        notes.push("private windows aren't supported".to_string());
+    }
+
+    if let Some(parameters) = entry.get("X-WebApp-CustomParameters")
+        && !parameters.is_empty()
+    {
+        notes.push(format!(
+            "custom browser parameters aren't supported ({})",
+            parameters
+        ));
+    }
+
+    notes
+}
+
+// The browser an entry runs. Web App Manager starts Firefox-based browsers through
+// `sh -c '... firefox --class ...'`, so the browser is looked for inside the script.
+fn browser_program(exec: &[String]) -> Option<PathBuf> {
+    let program = exec.first()?;
+    let name = Path::new(program)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or_default();
+    match name {
+        "flatpak" => None,
+        "sh" | "bash" | "env" => exec[1..]
+            .iter()
+            .flat_map(|arg| arg.split_whitespace())
+            .map(|word| word.trim_matches(['\'', '"']))
+            .find(|word| browsers::family(word) != BrowserFamily::Other)
+            .and_then(utils::find_in_path),
+        _ => utils::find_in_path(program),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::app_data;
+    use crate::test::create_test_config::create_test_config;
+
+    #[test]
+    fn test_import() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        std::fs::create_dir_all(&config.app_data_path).unwrap();
+        let browser_path = config.app_data_path.join("chromium");
+        std::fs::write(&browser_path, "").unwrap();
+        std::fs::set_permissions(&browser_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let content = format!(
+            "[Desktop Entry]\nVersion=1.0\nName=YouTube\nComment=Web App\nExec={} --app=\"https://www.youtube.com\" --class=WebApp-YouTube6174 --name=WebApp-YouTube6174 --user-data-dir=/home/me/.local/share/ice/profiles/YouTube6174\nTerminal=false\nX-MultipleArgs=false\nType=Application\nIcon=/home/me/.local/share/ice/icons/YouTube.png\nCategories=GTK;WebApps;\nStartupWMClass=WebApp-YouTube6174\nStartupNotify=true\nX-WebApp-Browser=Chromium\nX-WebApp-URL=https://www.youtube.com\nX-WebApp-CustomParameters=\nX-WebApp-Navbar=false\nX-WebApp-PrivateWindow=false\nX-WebApp-Isolated=true\n",
+            browser_path.display()
+        );
+        std::fs::write(
+            config.desktop_data_path.join("webapp-YouTube6174.desktop"),
+            content,
+        )
+        .unwrap();
+
+        let report = import(&config).unwrap();
+        assert_eq!(report.imported, vec!["YouTube".to_string()]);
+        assert!(report.notes.is_empty());
+
+        let app = app_data::get_app("YouTube", &config).unwrap().unwrap();
+        assert_eq!(app.url, "https://www.youtube.com");
+        assert_eq!(
+            app.icon.as_deref(),
+            Some("/home/me/.local/share/ice/icons/YouTube.png")
+        );
+        assert_eq!(
+            app.browser_path.as_deref(),
+            Some(browser_path.to_str().unwrap())
+        );
+        assert!(app.isolated);
+        assert_eq!(
+            app.profile_path.as_deref(),
+            Some("/home/me/.local/share/ice/profiles/YouTube6174")
+        );
+    }
+
+    #[test]
+    fn test_import_firefox() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        std::fs::create_dir_all(&config.app_data_path).unwrap();
+        let browser_path = config.app_data_path.join("firefox");
+        std::fs::write(&browser_path, "").unwrap();
+        std::fs::set_permissions(&browser_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let content = format!(
+            "[Desktop Entry]\nName=Proton Mail\nExec=sh -c 'XAPP_FORCE_GTKWINDOW_ICON=proton {} --class WebApp-Proton --profile /home/me/.local/share/ice/firefox/Proton --no-remote \"https://mail.proton.me\"'\nType=Application\nX-WebApp-Browser=Firefox\nX-WebApp-URL=https://mail.proton.me\nX-WebApp-CustomParameters=--kiosk\nX-WebApp-Isolated=true\n",
+            browser_path.display()
+        );
+        std::fs::write(
+            config.desktop_data_path.join("webapp-Proton1234.desktop"),
+            content,
+        )
+        .unwrap();
+
+        let report = import(&config).unwrap();
+        assert_eq!(report.imported, vec!["Proton Mail".to_string()]);
+        // the profile and the custom parameters
+        assert_eq!(report.notes.len(), 2);
+
+        let app = app_data::get_app("Proton Mail", &config).unwrap().unwrap();
+        assert_eq!(app.engine, Some(Engine::Firefox));
+        assert_eq!(
+            app.browser_path.as_deref(),
+            Some(browser_path.to_str().unwrap())
+        );
+        assert!(app.isolated);
+        assert!(app.profile_path.is_none());
+    }
+
+    #[test]
+    fn test_import_epiphany() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        std::fs::create_dir_all(&config.app_data_path).unwrap();
+        let browser_path = config.app_data_path.join("epiphany");
+        std::fs::write(&browser_path, "").unwrap();
+        std::fs::set_permissions(&browser_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let content = format!(
+            "[Desktop Entry]\nName=Proton Mail\nExec={} --application-mode --profile=\"/home/me/.local/share/ice/epiphany/Proton\" \"https://mail.proton.me\"\nType=Application\nX-WebApp-Browser=Epiphany\nX-WebApp-URL=https://mail.proton.me\nX-WebApp-Isolated=true\n",
+            browser_path.display()
+        );
+        std::fs::write(
+            config.desktop_data_path.join("webapp-Proton1234.desktop"),
+            content,
+        )
+        .unwrap();
+
+        let report = import(&config).unwrap();
+        assert_eq!(report.imported, vec!["Proton Mail".to_string()]);
+        assert_eq!(report.notes.len(), 1);
+
+        let app = app_data::get_app("Proton Mail", &config).unwrap().unwrap();
+        assert_eq!(app.engine, Some(Engine::Epiphany));
+        assert_eq!(
+            app.browser_path.as_deref(),
+            Some(browser_path.to_str().unwrap())
+        );
+        assert!(app.profile_path.is_none());
+    }
+
+    #[test]
+    fn test_import_unsupported_browser() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let content = "[Desktop Entry]\nName=Proton Mail\nExec=falkon --no-extensions --profile Proton \"https://mail.proton.me\"\nType=Application\nX-WebApp-Browser=Falkon\nX-WebApp-URL=https://mail.proton.me\nX-WebApp-CustomParameters=--kiosk\nX-WebApp-Isolated=true\n";
+        std::fs::write(
+            config.desktop_data_path.join("webapp-Proton1234.desktop"),
+            content,
+        )
+        .unwrap();
+
+        let report = import(&config).unwrap();
+        assert_eq!(report.imported, vec!["Proton Mail".to_string()]);
+        assert_eq!(report.notes.len(), 2);
+
+        let app = app_data::get_app("Proton Mail", &config).unwrap().unwrap();
+        assert!(app.browser_path.is_none());
+        assert!(app.engine.is_none());
+        assert!(app.isolated);
+        assert!(app.profile_path.is_none());
+    }
+}
@@ -1,29 +1,113 @@
 use crate::app::config::Config;
-use crate::domain::app::App;
+use crate::app::export::{self, compositor};
+use crate::app::run::template;
+use crate::domain::app::{
+    App, CommandTemplate, Engine, LaunchOptions, NetworkOptions, SandboxMode, SessionFlags,
+    WindowRules,
+};
+use crate::domain::app_name::AppName;
 use crate::infra::app_data;
 use crate::infra::desktop_data;
+use crate::infra::desktop_entry;
 use crate::infra::icons;
+use crate::infra::shim_data;
+use std::collections::BTreeMap;
 
 #[derive(Debug)]
 pub enum InstallError {
     Io(std::io::Error),
     Desktop(desktop_data::DesktopDataError),
     AppData(app_data::ProjectDataError),
+    Shim(shim_data::ShimDataError),
+    Export(export::ExportError),
     InvalidData(String),
 }
 
-pub fn install(name: &str, url: &str, config: &Config) -> Result<(), InstallError> {
-    if ["install", "uninstall", "update", "list"].contains(&name.to_lowercase().as_str()) {
-        return Err(InstallError::InvalidData(
-            "App name cannot be a reserved word (install, uninstall, update, list)".to_string(),
-        ));
-    }
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct InstallOptions {
+    // create a launcher shim in the bin directory
+    #[serde(default)]
+    pub shim: bool,
+    #[serde(default)]
+    pub window: WindowRules,
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub localized_names: BTreeMap<String, String>,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub engine: Option<Engine>,
+    #[serde(default)]
+    pub launch: LaunchOptions,
+    // apps with network settings get an isolated profile
+    #[serde(default)]
+    pub network: NetworkOptions,
+    #[serde(default)]
+    pub command_template: CommandTemplate,
+    #[serde(default)]
+    pub session_flags: SessionFlags,
+    // apps in a sandbox get an isolated profile
+    #[serde(default)]
+    pub sandbox: Option<SandboxMode>,
+}
+
+// Subcommands of the CLI, which launchers running `tarantula <slug>` would run instead
+// of the app.
+pub const RESERVED_NAMES: [&str; 12] = [
+    "install",
+    "uninstall",
+    "update",
+    "list",
+    "config",
+    "import",
+    "export",
+    "action",
+    "autostart",
+    "doctor",
+    "recover",
+    "browsers",
+];
 
+pub fn install(
+    name: &str,
+    url: &str,
+    options: &InstallOptions,
+    config: &Config,
+) -> Result<(), InstallError> {
     let mut app = App {
         name: name.to_string(),
         url: url.to_string(),
         icon: None,
+        shim: options.shim,
+        window: options.window.clone(),
+        hotkey: options.hotkey.clone(),
+        tags: options.tags.clone(),
+        description: options.description.clone(),
+        keywords: options.keywords.clone(),
+        categories: options.categories.clone(),
+        localized_names: options.localized_names.clone(),
+        hidden: options.hidden,
+        engine: options.engine,
+        launch: options.launch.clone(),
+        isolated: !options.network.is_empty() || options.sandbox.is_some(),
+        network: options.network.clone(),
+        command_template: options.command_template.clone(),
+        session_flags: options.session_flags.clone(),
+        sandbox: options.sandbox,
+        ..Default::default()
     };
+    // before the icon is downloaded, so an install that fails neither fetches one nor
+    // overwrites the icon of the app it clashes with
+    validate(&mut app, config)?;
 
     match icons::store_icon(&app, &config) {
         Ok(icon_url) => match icon_url {
@@ -33,12 +117,94 @@ pub fn install(name: &str, url: &str, config: &Config) -> Result<(), InstallErro
         Err(e) => eprintln!("{:?}", e),
     }
 
-    app_data::add_app(app.clone(), config).map_err(|e| InstallError::AppData(e))?;
-    desktop_data::create_entry(&app, config).map_err(|e| InstallError::Desktop(e))?;
+    write(app, config)
+}
+
+// Registers an app that already has all of its data, e.g. one brought over by an importer.
+pub fn add(mut app: App, config: &Config) -> Result<(), InstallError> {
+    validate(&mut app, config)?;
+    write(app, config)
+}
+
+// Checks the app can be installed, normalizing its name.
+fn validate(app: &mut App, config: &Config) -> Result<(), InstallError> {
+    let name = validate_name(&app.name)?;
+    app.name = name.to_string();
+
+    if app_data::get_app(&app.name, config)
+        .map_err(InstallError::AppData)?
+        .is_some()
+    {
+        return Err(InstallError::InvalidData(format!(
+            "An app named {} is already installed",
+            app.name
+        )));
+    }
+
+    if app.shim {
+        check_shim(&name, config)?;
+    }
+
+    if let Some(hotkey) = &app.hotkey {
+        compositor::parse_hotkey(hotkey).map_err(InstallError::InvalidData)?;
+    }
+    app.window.validate().map_err(InstallError::InvalidData)?;
+
+    app.launch.validate().map_err(InstallError::InvalidData)?;
+    app.validate_isolation()
+        .map_err(InstallError::InvalidData)?;
+    template::validate(&app.command_template).map_err(InstallError::InvalidData)?;
+
+    if let Some(locale) = app
+        .localized_names
+        .keys()
+        .find(|l| !desktop_entry::is_valid_locale(l))
+    {
+        return Err(InstallError::InvalidData(format!(
+            "Invalid locale: {}",
+            locale
+        )));
+    }
+
+    Ok(())
+}
+
+fn write(app: App, config: &Config) -> Result<(), InstallError> {
+    app_data::add_app(app.clone(), config).map_err(InstallError::AppData)?;
+    desktop_data::create_entry(&app, config).map_err(InstallError::Desktop)?;
+    desktop_data::sync_menu(config).map_err(InstallError::Desktop)?;
+    if app.shim {
+        shim_data::create_shim(&app, config).map_err(InstallError::Shim)?;
+    }
+    compositor::sync(config).map_err(InstallError::Export)?;
 
     Ok(())
 }
 
+fn check_shim(name: &AppName, config: &Config) -> Result<(), InstallError> {
+    shim_data::check_shim(name, config).map_err(|e| match e {
+        shim_data::ShimDataError::CommandExists(path) => InstallError::InvalidData(format!(
+            "The {} launcher would shadow the existing command {}",
+            shim_data::shim_name(name),
+            path.display()
+        )),
+        e => InstallError::Shim(e),
+    })
+}
+
+fn validate_name(name: &str) -> Result<AppName, InstallError> {
+    let name = AppName::parse(name).map_err(|e| InstallError::InvalidData(e.to_string()))?;
+
+    if RESERVED_NAMES.contains(&name.slug().as_str()) {
+        return Err(InstallError::InvalidData(format!(
+            "App name cannot be a reserved word ({})",
+            RESERVED_NAMES.join(", ")
+        )));
+    }
+
+    Ok(name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,11 +215,118 @@ mod tests {
         let name = "Test App";
         let url = "https://example.com";
 
-        install(name, url, &config).unwrap();
+        install(name, url, &InstallOptions::default(), &config).unwrap();
 
         let apps = app_data::get_apps(&config).unwrap();
         assert_eq!(apps.len(), 1);
         assert_eq!(apps[0].name, name);
         assert_eq!(apps[0].url, url);
     }
+
+    #[test]
+    fn test_install_with_shim() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        let options = InstallOptions {
+            shim: true,
+            ..Default::default()
+        };
+
+        install("Test App", "https://example.com", &options, &config).unwrap();
+        assert!(config.bin_path.join("test-app").exists());
+
+        // sh is already a command on $PATH
+        let result = install("sh", "https://example.com", &options, &config);
+        assert!(matches!(result, Err(InstallError::InvalidData(_))));
+        assert!(app_data::get_app("sh", &config).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_install_rejects_unsafe_names() {
+        let (config, _dir, desktop_dir) = crate::test::create_test_config::create_test_config();
+        let options = InstallOptions {
+            shim: true,
+            ..Default::default()
+        };
+
+        for name in [
+            "Mail\nExec=sh -c reboot",
+            "../../../.config/autostart/evil",
+            "/tmp/evil",
+            "$(reboot)",
+            "mail' ; reboot ; '",
+        ] {
+            assert!(matches!(
+                install(name, "https://example.com", &options, &config),
+                Err(InstallError::InvalidData(_))
+            ));
+        }
+
+        assert_eq!(app_data::get_apps(&config).unwrap().len(), 0);
+        assert_eq!(std::fs::read_dir(desktop_dir.path()).unwrap().count(), 0);
+        assert!(!config.bin_path.exists());
+    }
+
+    #[test]
+    fn test_install_rejects_reserved_names() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+
+        // launchers run `tarantula <slug>`, which would run the subcommand instead
+        for name in ["Autostart", "Doctor", "Recover", "Browsers", "Config"] {
+            assert!(matches!(
+                install(
+                    name,
+                    "https://example.com",
+                    &InstallOptions::default(),
+                    &config
+                ),
+                Err(InstallError::InvalidData(_))
+            ));
+        }
+        assert_eq!(app_data::get_apps(&config).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_install_checks_before_icon() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        let icon = config.app_data_path.join("test_app.ico");
+        let app = App {
+            name: "Test App".to_string(),
+            url: "https://example.com".to_string(),
+            icon: Some(icon.display().to_string()),
+            ..Default::default()
+        };
+        add(app, &config).unwrap();
+        std::fs::write(&icon, "icon").unwrap();
+
+        assert!(matches!(
+            install(
+                "test app",
+                "https://example.com",
+                &InstallOptions::default(),
+                &config
+            ),
+            Err(InstallError::InvalidData(_))
+        ));
+        assert_eq!(std::fs::read_to_string(&icon).unwrap(), "icon");
+    }
+
+    #[test]
+    fn test_add_rejects_duplicates() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        let app = App {
+            name: "Test App".to_string(),
+            url: "https://example.com".to_string(),
+            icon: None,
+            ..Default::default()
+        };
+
+        add(app.clone(), &config).unwrap();
+        assert!(matches!(
+            add(app, &config),
+            Err(InstallError::InvalidData(_))
+        ));
+
+        let apps = app_data::get_apps(&config).unwrap();
+        assert_eq!(apps.len(), 1);
+    }
 }
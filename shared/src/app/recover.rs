@@ -0,0 +1,172 @@
+use std::path::PathBuf;
+
+use crate::app::config::Config;
+use crate::app::install::{self, InstallError};
+use crate::domain::app::App;
+use crate::infra::app_data::{self, ProjectDataError};
+use crate::infra::desktop_data::{self, DesktopDataError};
+
+#[derive(Debug)]
+pub enum RecoverError {
+    Io(std::io::Error),
+    AppData(ProjectDataError),
+    Desktop(DesktopDataError),
+    Install(InstallError),
+}
+
+#[derive(Debug, Default)]
+pub struct RecoverReport {
+    pub recovered: Vec<String>,
+    // app name and how an entry differs from the registry, which is left as it is
+    pub conflicts: Vec<(String, String)>,
+    // entry file and why no app could be recovered from it
+    pub skipped: Vec<(PathBuf, String)>,
+    // where an apps.json that couldn't be read was moved to
+    pub backup: Option<PathBuf>,
+}
+
+// Rebuilds the registry from the recovery keys of the desktop entries in the applications directory.
+// Apps that are already registered are kept, differences are reported as conflicts.
+pub fn recover(config: &Config) -> Result<RecoverReport, RecoverError> {
+    let mut report = RecoverReport::default();
+
+    if let Err(ProjectDataError::JSON(_)) = app_data::get_apps(config) {
+        report.backup = Some(app_data::backup_data_file(config).map_err(RecoverError::Io)?);
+    }
+
+    let entries =
+        desktop_data::read_entries(&config.desktop_data_path).map_err(RecoverError::Desktop)?;
+    for entry in entries {
+        let app = match desktop_data::app_from_entry(&entry) {
+            Ok(Some(app)) => app,
+            Ok(None) => {
+                if desktop_data::launched_app(&entry, config).is_some() {
+                    report.skipped.push((
+                        entry.path,
+                        "Written by an older version of tarantula without recovery data"
+                            .to_string(),
+                    ));
+                }
+                continue;
+            }
+            Err(DesktopDataError::InvalidData(reason)) => {
+                report.skipped.push((entry.path, reason));
+                continue;
+            }
+            Err(e) => return Err(RecoverError::Desktop(e)),
+        };
+
+        let existing = app_data::get_app(&app.name, config).map_err(RecoverError::AppData)?;
+        if let Some(existing) = existing {
+            let differences = differences(&existing, &app);
+            if !differences.is_empty() {
+                report.conflicts.push((
+                    existing.name,
+                    format!(
+                        "{} has a different {} than the registry",
+                        entry.path.display(),
+                        differences.join(", ")
+                    ),
+                ));
+            }
+            continue;
+        }
+
+        let name = app.name.clone();
+        match install::add(app, config) {
+            Ok(_) => report.recovered.push(name),
+            Err(InstallError::InvalidData(reason)) => report.skipped.push((entry.path, reason)),
+            Err(e) => return Err(RecoverError::Install(e)),
+        }
+    }
+
+    Ok(report)
+}
+
+// Settings that differ between the two apps, by their name in apps.json.
+fn differences(a: &App, b: &App) -> Vec<String> {
+    let (a, b) = match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(serde_json::Value::Object(a)), Ok(serde_json::Value::Object(b))) => (a, b),
+        _ => return Vec::new(),
+    };
+
+    let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| a.get(*key) != b.get(*key))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recover() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        install::install(
+            "Gmail",
+            "https://mail.google.com",
+            &install::InstallOptions {
+                tags: vec!["Work".to_string()],
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+        install::install(
+            "Slack",
+            "https://app.slack.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+        let before = app_data::get_apps(&config).unwrap();
+
+        // a registry that can't be read is moved aside and rebuilt
+        let apps_file = config.app_data_path.join("apps.json");
+        std::fs::write(&apps_file, "[{\"name\": \"Gma").unwrap();
+        let report = recover(&config).unwrap();
+        assert_eq!(report.recovered, vec!["Gmail", "Slack"]);
+        assert_eq!(
+            report.backup,
+            Some(config.app_data_path.join("apps.json.bak"))
+        );
+        assert_eq!(
+            serde_json::to_value(app_data::get_apps(&config).unwrap()).unwrap(),
+            serde_json::to_value(&before).unwrap()
+        );
+
+        // registered apps are kept, and entries that disagree with them are reported
+        let mut slack = app_data::get_app("Slack", &config).unwrap().unwrap();
+        slack.url = "https://work.slack.com".to_string();
+        app_data::update_app("Slack", &slack, &config).unwrap();
+        desktop_data::write_entry(
+            &config.desktop_data_path.join("old.desktop"),
+            &[("Name", "Old"), ("Exec", "tarantula old")],
+        )
+        .unwrap();
+        let report = recover(&config).unwrap();
+        assert!(report.recovered.is_empty());
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].0, "Slack");
+        assert!(
+            report.conflicts[0]
+                .1
+                .ends_with("has a different url than the registry")
+        );
+        assert_eq!(
+            report.skipped,
+            vec![(
+                config.desktop_data_path.join("old.desktop"),
+                "Written by an older version of tarantula without recovery data".to_string()
+            )]
+        );
+        assert_eq!(
+            app_data::get_app("Slack", &config).unwrap().unwrap().url,
+            "https://work.slack.com"
+        );
+    }
+}
@@ -1,8 +1,21 @@
-use std::path::Path;
+pub mod chromium;
+pub mod embedded;
+pub mod epiphany;
+pub mod firefox;
+pub mod sandbox;
+pub mod session;
+pub mod template;
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
 use std::process::Command;
+use std::time::Duration;
 
 use crate::app::config;
+use crate::domain::app::{App, Engine, LaunchOptions, NetworkOptions, SandboxMode, proxy_address};
+use crate::domain::app_name::AppName;
 use crate::infra::app_data::{self, ProjectDataError};
+use crate::infra::browsers::{self, BrowserCommand, BrowserFamily, Sandbox, XdgDirs};
 use crate::utils;
 
 #[derive(Debug)]
@@ -12,14 +25,184 @@ pub enum RunError {
     Io(std::io::Error),
 }
 
-pub fn run(app_name: &str, config: &config::Config) -> Result<(), RunError> {
-    let url = match app_data::get_app(app_name, config) {
+// What an engine needs to open an app in a window of its own.
+#[derive(Debug, Clone)]
+pub struct Launch {
+    pub url: String,
+    // window class (X11) and app id (Wayland)
+    pub class: String,
+    pub profile_path: Option<PathBuf>,
+    pub minimized: bool,
+    pub options: LaunchOptions,
+    pub network: NetworkOptions,
+}
+
+#[derive(Debug, Default)]
+pub struct RunOptions {
+    // URL or path to open instead of the app's start page
+    pub path: Option<String>,
+    // seconds to wait before launching, used by autostart entries
+    pub delay: Option<u32>,
+    // ask the browser to start the window minimized, browsers without support ignore it
+    pub minimized: bool,
+    // finds the tarantula UI binary, which the embedded engine opens apps in. It's only
+    // called when an app opens in the embedded engine.
+    pub ui_binary: Option<fn() -> Option<PathBuf>>,
+}
+
+// The command an app is launched with. It runs without a shell, so the words reach
+// the program as they are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandLine {
+    pub program: String,
+    pub args: Vec<String>,
+    // set on top of the inherited environment
+    pub env: Vec<(String, String)>,
+    // name the program runs under, which GTK names the embedded engine's windows after
+    pub arg0: Option<String>,
+    // how parts of the command were chosen, for diagnostics
+    pub notes: Vec<String>,
+}
+
+impl CommandLine {
+    fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        command.envs(self.env.iter().map(|(name, value)| (name, value)));
+        #[cfg(unix)]
+        if let Some(arg0) = &self.arg0 {
+            use std::os::unix::process::CommandExt;
+            command.arg0(arg0);
+        }
+        command
+    }
+}
+
+// Written as a shell command line that runs the same program with the same arguments.
+impl std::fmt::Display for CommandLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in &self.env {
+            write!(f, "{}={} ", name, utils::shell_quote(value))?;
+        }
+        write!(f, "{}", utils::shell_quote(&self.program))?;
+        for arg in &self.args {
+            write!(f, " {}", utils::shell_quote(arg))?;
+        }
+        Ok(())
+    }
+}
+
+// An app resolved to the command that opens it, with the profile files its engine
+// reads not written yet.
+struct Prepared {
+    app: App,
+    engine: Engine,
+    browser: Option<BrowserCommand>,
+    launch: Launch,
+    command: CommandLine,
+    sandbox: Option<Sandboxed>,
+}
+
+// A launch wrapped in bubblewrap or firejail.
+struct Sandboxed {
+    exposure: sandbox::Exposure,
+    // generated firejail profile, bubblewrap takes everything as arguments
+    firejail_profile: Option<PathBuf>,
+}
+
+pub fn run(
+    app_name: &str,
+    options: &RunOptions,
+    config: &config::Config,
+) -> Result<(), RunError> {
+    let Prepared {
+        app,
+        engine,
+        browser,
+        launch,
+        command,
+        sandbox,
+    } = prepare(app_name, options, config)?;
+
+    if let Some(profile_path) = &launch.profile_path {
+        if !profile_path.exists()
+            && let Some(Sandbox::Flatpak(id)) = browser.as_ref().and_then(|b| b.sandbox.as_ref())
+            && app.profile_path.is_some()
+        {
+            eprintln!(
+                "{} can't write outside its sandbox, allow it with `flatpak override --user --filesystem={} {}`",
+                id,
+                profile_path.display(),
+                id
+            );
+        }
+        std::fs::create_dir_all(profile_path).map_err(RunError::Io)?;
+
+        match engine {
+            Engine::Chromium => {
+                chromium::write_local_state(profile_path, &launch.network).map_err(RunError::Io)?
+            }
+            Engine::Firefox => {
+                firefox::write_profile(profile_path, &launch.options).map_err(RunError::Io)?
+            }
+            Engine::Epiphany => epiphany::write_profile(profile_path, &app, config)?,
+            Engine::Embedded => {}
+        }
+    }
+
+    if let Some(sandbox) = &sandbox {
+        // bound into the sandbox, which fails for missing paths
+        std::fs::create_dir_all(&sandbox.exposure.downloads).map_err(RunError::Io)?;
+        if let Some(path) = &sandbox.firejail_profile {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(RunError::Io)?;
+            }
+            std::fs::write(path, sandbox::firejail_profile(&sandbox.exposure))
+                .map_err(RunError::Io)?;
+        }
+    }
+
+    if let Some(delay) = options.delay {
+        std::thread::sleep(std::time::Duration::from_secs(delay.into()));
+    }
+
+    if engine == Engine::Chromium
+        && let Some(proxy) = &launch.network.proxy
+    {
+        check_proxy(proxy)?;
+    }
+
+    match command.command().spawn() {
+        Ok(_) => Ok(()),
+        Err(e) => Err(RunError::LaunchFailed(e.to_string())),
+    }
+}
+
+// The command `run` would launch the app with, without writing anything.
+pub fn command_line(
+    app_name: &str,
+    options: &RunOptions,
+    config: &config::Config,
+) -> Result<CommandLine, RunError> {
+    Ok(prepare(app_name, options, config)?.command)
+}
+
+fn prepare(
+    app_name: &str,
+    options: &RunOptions,
+    config: &config::Config,
+) -> Result<Prepared, RunError> {
+    let app = match app_data::get_app(app_name, config) {
         Ok(app) => match app {
-            Some(a) => a.url,
+            Some(a) => a,
             None => {
                 let is_url = utils::is_url(app_name);
                 if is_url {
-                    app_name.to_string()
+                    App {
+                        name: app_name.to_string(),
+                        url: app_name.to_string(),
+                        ..Default::default()
+                    }
                 } else {
                     return Err(RunError::AppNotFound(app_name.to_string()));
                 }
@@ -34,90 +217,357 @@ pub fn run(app_name: &str, config: &config::Config) -> Result<(), RunError> {
         }
     };
 
-    let browser_path = match &config.browser_path {
-        Some(path) => path.clone(),
-        None => match get_browser_path() {
-            Some(path) => path,
-            None => {
-                return Err(RunError::LaunchFailed(
-                    "Could not determine default browser".to_string(),
-                ));
-            }
-        },
+    let (engine, browser) = engine_and_browser(&app, options, config)?;
+    for option in unsupported_options(engine, &app) {
+        eprintln!(
+            "The {} engine doesn't support {}, it is ignored",
+            engine, option
+        );
+    }
+
+    let url = match &options.path {
+        Some(path) => deep_link(&app.url, path)?,
+        None => app.url.clone(),
     };
 
-    match Command::new(browser_path)
-        .arg(format!("--app={}", url))
-        .spawn()
+    let profile_path = app_data::get_profile_path(&app, engine, browser.as_ref(), config);
+    // switches of a profile that's already open are ignored and a sandbox only exposes
+    // the app's profile, so the settings need their own profile
+    if engine == Engine::Chromium
+        && app.needs_isolation()
+        && (profile_path.is_none() || !app.isolated)
     {
-        Ok(_) => Ok(()),
-        Err(e) => Err(RunError::LaunchFailed(e.to_string())),
+        let settings = if app.network.is_empty() {
+            "sandbox"
+        } else {
+            "network settings"
+        };
+        return Err(RunError::LaunchFailed(format!(
+            "The {} of {} need an isolated profile, enable it with `tarantula update {} --isolated true`",
+            settings,
+            app.name,
+            utils::shell_quote(&app.name)
+        )));
+    }
+
+    let launch = Launch {
+        url,
+        class: app.window_class(),
+        profile_path,
+        minimized: options.minimized,
+        options: app.launch.clone(),
+        network: app.network.clone(),
+    };
+    let mut notes = Vec::new();
+    let engine_args = match engine {
+        Engine::Chromium => {
+            let mut args = Vec::new();
+            match session::detect() {
+                Some(session) => {
+                    let choice =
+                        session::choose(session, &app.session_flags, &config.session_flags);
+                    notes.push(choice.to_string());
+                    args.extend(choice.flags);
+                }
+                None => notes.push("no Wayland or X11 session found, no session flags".to_string()),
+            }
+            args.extend(chromium::args(&launch));
+            args
+        }
+        Engine::Firefox => firefox::args(&launch)?,
+        Engine::Epiphany => epiphany::args(&launch)?,
+        Engine::Embedded => embedded::args(&launch, &app)?,
+    };
+    let mut program = match &browser {
+        Some(browser) => [vec![browser.program.clone()], browser.args.clone()].concat(),
+        None => vec![
+            embedded::program(options.ui_binary)?
+                .display()
+                .to_string(),
+        ],
+    };
+    let embedded_program = program[0].clone();
+    let sandbox = match app.sandbox {
+        Some(mode) => {
+            let (words, sandboxed) =
+                sandbox_command(mode, &app, &launch, browser.as_ref(), config)?;
+            notes.push(format!("sandboxed with {}", words[0]));
+            program.splice(0..0, words);
+            Some(sandboxed)
+        }
+        None => None,
+    };
+
+    let command_template = app.command_template.or(&config.command_template);
+    template::validate(&command_template).map_err(RunError::LaunchFailed)?;
+    let mut words = template::expand(&command_template, &program, &engine_args)
+        .map_err(RunError::LaunchFailed)?
+        .into_iter();
+    let first = words.next().unwrap_or_default();
+    let arg0 =
+        (engine == Engine::Embedded && first == embedded_program).then(|| launch.class.clone());
+
+    let mut env = Vec::new();
+    // browsers and WebKit pick the interface language from $LANGUAGE, which takes ll_CC
+    if let Some(language) = &launch.options.language {
+        env.push(("LANGUAGE".to_string(), language.replace('-', "_")));
     }
+    env.extend(command_template.env);
+
+    Ok(Prepared {
+        app,
+        engine,
+        browser,
+        launch,
+        command: CommandLine {
+            program: first,
+            args: words.collect(),
+            env,
+            arg0,
+            notes,
+        },
+        sandbox,
+    })
 }
 
-fn get_browser_path() -> Option<String> {
-    let default_browser = match std::process::Command::new("xdg-settings")
-        .arg("get")
-        .arg("default-web-browser")
-        .output()
-        .ok()
-    {
-        Some(output) => String::from_utf8_lossy(&output.stdout).trim().to_string(),
-        None => return None,
+// The words that run the browser in the sandbox, put before its command.
+fn sandbox_command(
+    mode: SandboxMode,
+    app: &App,
+    launch: &Launch,
+    browser: Option<&BrowserCommand>,
+    config: &config::Config,
+) -> Result<(Vec<String>, Sandboxed), RunError> {
+    let failed = |reason: &str| {
+        RunError::LaunchFailed(format!(
+            "{} can't run in a sandbox, {}, or turn it off with `tarantula update {} --sandbox \"\"`",
+            app.name,
+            reason,
+            utils::shell_quote(&app.name)
+        ))
     };
+    let browser =
+        browser.ok_or_else(|| failed("the embedded engine runs in tarantula's window"))?;
+    if let Some(Sandbox::Flatpak(name) | Sandbox::Snap(name)) = &browser.sandbox {
+        return Err(failed(&format!("{} runs in a sandbox of its own", name)));
+    }
+    let (tool, tool_path) = sandbox::find_tool(mode).ok_or_else(|| match mode {
+        SandboxMode::Auto => failed("install bubblewrap or firejail"),
+        mode => failed(&format!("install {}", mode)),
+    })?;
+    let profile_path = launch
+        .profile_path
+        .as_ref()
+        .ok_or_else(|| failed("the app has no profile of its own"))?;
+    let exposure = sandbox::Exposure::from_env(profile_path, &browser.program)
+        .ok_or_else(|| failed("the home directory isn't known"))?;
 
-    if Path::new("/usr/share/applications/")
-        .join(&default_browser)
-        .exists()
-    {
-        let exec_path =
-            get_exec_from_desktop(&Path::new("/usr/share/applications/").join(&default_browser));
-        if exec_path.is_some() {
-            return exec_path;
+    let tool_path = tool_path.display().to_string();
+    let (words, firejail_profile) = match tool {
+        sandbox::Tool::Bubblewrap => (
+            [vec![tool_path], sandbox::bwrap_args(&exposure)].concat(),
+            None,
+        ),
+        sandbox::Tool::Firejail => {
+            let slug = AppName::parse(&app.name)
+                .map_err(|_| failed("the app has no profile of its own"))?
+                .slug();
+            let path = config
+                .app_data_path
+                .join("sandbox")
+                .join(format!("{}.profile", slug));
+            let words = vec![
+                tool_path,
+                "--quiet".to_string(),
+                format!("--profile={}", path.display()),
+            ];
+            (words, Some(path))
         }
+    };
+    Ok((
+        words,
+        Sandboxed {
+            exposure,
+            firejail_profile,
+        },
+    ))
+}
+
+// Resolves a deep link against the app's URL, so "/inbox" opens that page of the app.
+pub fn deep_link(app_url: &str, path: &str) -> Result<String, RunError> {
+    if utils::is_url(path) {
+        return Ok(path.to_string());
     }
 
-    if Path::new("/etc/xdg/autostart/")
-        .join(&default_browser)
-        .exists()
-    {
-        let exec_path =
-            get_exec_from_desktop(&Path::new("/etc/xdg/autostart/").join(&default_browser));
-        if exec_path.is_some() {
-            return exec_path;
+    let base = url::Url::parse(app_url)
+        .map_err(|e| RunError::LaunchFailed(format!("Invalid app URL {}: {}", app_url, e)))?;
+    let url = base
+        .join(path)
+        .map_err(|e| RunError::LaunchFailed(format!("Invalid path {}: {}", path, e)))?;
+
+    Ok(url.to_string())
+}
+
+// Fails unless the proxy accepts connections, as apps that must go through it
+// would otherwise load without it or not at all.
+fn check_proxy(proxy: &str) -> Result<(), RunError> {
+    let (host, port) = proxy_address(proxy).map_err(RunError::LaunchFailed)?;
+    let unreachable = |e: std::io::Error| {
+        RunError::LaunchFailed(format!("The proxy {} isn't reachable: {}", proxy, e))
+    };
+    let mut error = None;
+    let addresses = (host.as_str(), port)
+        .to_socket_addrs()
+        .map_err(unreachable)?;
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, Duration::from_secs(3)) {
+            Ok(_) => return Ok(()),
+            Err(e) => error = Some(e),
+        }
+    }
+    Err(unreachable(error.unwrap_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "no address found")
+    })))
+}
+
+// Launch and network options set for the app that the engine has no equivalent for.
+fn unsupported_options(engine: Engine, app: &App) -> Vec<&'static str> {
+    let options = &app.launch;
+    let mut unsupported = Vec::new();
+    let geometry = options.geometry.is_some();
+    let scale = options.scale.is_some();
+    let user_agent = options.user_agent.is_some();
+    match engine {
+        Engine::Chromium => {}
+        Engine::Firefox => {
+            if geometry {
+                unsupported.push("the window geometry");
+            }
         }
+        Engine::Epiphany => {
+            if geometry {
+                unsupported.push("the window geometry");
+            }
+            if scale {
+                unsupported.push("the scale");
+            }
+            if user_agent {
+                unsupported.push("the user agent");
+            }
+            if options.tab {
+                unsupported.push("tabs");
+            }
+        }
+        Engine::Embedded => {
+            if options.tab {
+                unsupported.push("tabs");
+            }
+        }
+    }
+    if engine != Engine::Chromium && !app.network.is_empty() {
+        unsupported.push("the network settings");
+    }
+    if engine != Engine::Chromium && !app.session_flags.is_empty() {
+        unsupported.push("the session flags");
     }
+    unsupported
+}
 
-    if Path::new("/usr/bin/").join(&default_browser).exists() {
-        return Some("/usr/bin/".to_owned() + &default_browser);
+pub fn engine_for(family: BrowserFamily) -> Option<Engine> {
+    match family {
+        BrowserFamily::Chromium => Some(Engine::Chromium),
+        BrowserFamily::Firefox => Some(Engine::Firefox),
+        BrowserFamily::Epiphany => Some(Engine::Epiphany),
+        BrowserFamily::Other => None,
     }
+}
 
-    if Path::new("/usr/local/bin/").join(&default_browser).exists() {
-        return Some("/usr/local/bin/".to_owned() + &default_browser);
+// The browsers an engine runs in, None for the embedded engine, which needs no browser.
+fn family_for(engine: Engine) -> Option<BrowserFamily> {
+    match engine {
+        Engine::Chromium => Some(BrowserFamily::Chromium),
+        Engine::Firefox => Some(BrowserFamily::Firefox),
+        Engine::Epiphany => Some(BrowserFamily::Epiphany),
+        Engine::Embedded => None,
     }
+}
 
-    if Path::new("/snap/bin/").join(&default_browser).exists() {
-        return Some("/snap/bin/".to_owned() + &default_browser);
+// The engine to run the app in and the browser for it. Without any browser to run
+// an app in, the app opens in tarantula's own window.
+fn engine_and_browser(
+    app: &App,
+    options: &RunOptions,
+    config: &config::Config,
+) -> Result<(Engine, Option<BrowserCommand>), RunError> {
+    if app.engine == Some(Engine::Embedded) {
+        return Ok((Engine::Embedded, None));
     }
 
-    None
+    match (browser_command(app, config), app.engine) {
+        (Some(browser), Some(engine)) => Ok((engine, Some(browser))),
+        (Some(browser), None) => match engine_for(browser.family()) {
+            Some(engine) => Ok((engine, Some(browser))),
+            None => Err(RunError::LaunchFailed(format!(
+                "{} isn't Chromium-, Firefox- or Epiphany-based and can't run apps",
+                browser
+            ))),
+        },
+        (None, None) if options.ui_binary.is_some() => Ok((Engine::Embedded, None)),
+        (None, Some(engine)) => Err(RunError::LaunchFailed(format!(
+            "No browser found for the {} engine, install one or set it with `tarantula update {} --browser <path>`",
+            engine,
+            utils::shell_quote(&app.name)
+        ))),
+        (None, None) => Err(RunError::LaunchFailed(
+            "No Chromium- or Firefox-based browser found, install one or set it with `tarantula config --browser <path>`"
+                .to_string(),
+        )),
+    }
 }
 
-fn get_exec_from_desktop(path: &Path) -> Option<String> {
-    let file_content = std::fs::read_to_string(path).ok()?;
-    for line in file_content.lines() {
-        if line.starts_with("Exec=") {
-            let exec_line = line.trim_start_matches("Exec=").trim();
-            let exec_parts: Vec<&str> = exec_line.split_whitespace().collect();
-            if !exec_parts.is_empty() {
-                let exec_path = exec_parts[0];
-                if exec_path.contains("%") {
-                    return Some(exec_path.split('%').next().unwrap().to_string());
-                } else {
-                    return Some(exec_path.to_string());
-                }
+// The app's browser, then the configured one, then the first installed browser that can run apps.
+// Browsers of another engine than the one the app is set to are skipped.
+fn browser_command(app: &App, config: &config::Config) -> Option<BrowserCommand> {
+    for path in app.browser_path.iter().chain(config.browser_path.iter()) {
+        match BrowserCommand::parse(path).filter(BrowserCommand::exists) {
+            Some(command)
+                if app
+                    .engine
+                    .is_none_or(|engine| engine_for(command.family()) == Some(engine)) =>
+            {
+                return Some(command);
             }
+            Some(_) => {}
+            None => eprintln!("Browser not found: {}", path),
         }
     }
-    None
+
+    let dirs = XdgDirs::from_env(config);
+    let browser = match app.engine {
+        Some(engine) => browsers::find(&dirs, family_for(engine)?),
+        None => browsers::fallback(&dirs),
+    };
+    browser.map(|browser| browser.command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deep_link() {
+        let app_url = "https://mail.google.com/mail/u/0/";
+        assert_eq!(
+            deep_link(app_url, "/calendar").unwrap(),
+            "https://mail.google.com/calendar"
+        );
+        assert_eq!(
+            deep_link(app_url, "#inbox").unwrap(),
+            "https://mail.google.com/mail/u/0/#inbox"
+        );
+        assert_eq!(
+            deep_link(app_url, "https://example.com/").unwrap(),
+            "https://example.com/"
+        );
+    }
 }
@@ -0,0 +1,187 @@
+use std::path::Path;
+
+use super::Launch;
+use crate::domain::app::NetworkOptions;
+
+// Chromium opens the URL in a window without tabs or toolbars with --app.
+pub fn args(launch: &Launch) -> Vec<String> {
+    let options = &launch.options;
+    let mut args = vec![
+        format!("--class={}", launch.class),
+        format!("--name={}", launch.class),
+    ];
+    if let Some(profile_path) = &launch.profile_path {
+        args.push(format!("--user-data-dir={}", profile_path.display()));
+    }
+    if let Some(geometry) = &options.geometry {
+        args.push(format!(
+            "--window-size={},{}",
+            geometry.width, geometry.height
+        ));
+        if let Some((x, y)) = geometry.position {
+            args.push(format!("--window-position={},{}", x, y));
+        }
+    }
+    if let Some(scale) = options.scale {
+        args.push(format!("--force-device-scale-factor={}", scale));
+    }
+    if let Some(language) = &options.language {
+        args.push(format!("--lang={}", language));
+    }
+    if let Some(user_agent) = &options.user_agent {
+        args.push(format!("--user-agent={}", user_agent));
+    }
+    let network = &launch.network;
+    if let Some(proxy) = &network.proxy {
+        args.push(format!("--proxy-server={}", proxy));
+    }
+    if !network.proxy_bypass.is_empty() {
+        args.push(format!(
+            "--proxy-bypass-list={}",
+            network.proxy_bypass.join(";")
+        ));
+    }
+    if let Some(host_rules) = &network.host_rules {
+        args.push(format!("--host-resolver-rules={}", host_rules));
+    }
+    if launch.minimized {
+        args.push("--start-minimized".to_string());
+    }
+    // the URL goes last, so a tab isn't mistaken for a flag
+    if options.tab {
+        args.push(launch.url.clone());
+    } else {
+        args.push(format!("--app={}", launch.url));
+    }
+    args
+}
+
+// Chromium has no switch for DNS-over-HTTPS, it reads the mode and servers from
+// the profile's Local State, which keeps the browser's other settings.
+pub fn write_local_state(profile_path: &Path, network: &NetworkOptions) -> std::io::Result<()> {
+    let Some(mode) = network.secure_dns else {
+        return Ok(());
+    };
+    let path = profile_path.join("Local State");
+    let mut state = match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|_| serde_json::json!({})),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => serde_json::json!({}),
+        Err(e) => return Err(e),
+    };
+    if !state.is_object() {
+        state = serde_json::json!({});
+    }
+    state["dns_over_https"] = serde_json::json!({
+        "mode": mode.to_string(),
+        "templates": network.doh_template.clone().unwrap_or_default(),
+    });
+    std::fs::write(&path, state.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::app::LaunchOptions;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_args() {
+        let launch = Launch {
+            url: "https://mail.google.com".to_string(),
+            class: "tarantula-gmail".to_string(),
+            profile_path: Some(PathBuf::from("/profiles/gmail")),
+            minimized: true,
+            options: LaunchOptions::default(),
+            network: NetworkOptions::default(),
+        };
+        assert_eq!(
+            args(&launch),
+            vec![
+                "--class=tarantula-gmail",
+                "--name=tarantula-gmail",
+                "--user-data-dir=/profiles/gmail",
+                "--start-minimized",
+                "--app=https://mail.google.com",
+            ]
+        );
+
+        let launch = Launch {
+            profile_path: None,
+            minimized: false,
+            options: LaunchOptions {
+                geometry: Some("1200x800+100+50".parse().unwrap()),
+                scale: Some(1.25),
+                language: Some("de-DE".to_string()),
+                user_agent: Some("Mozilla/5.0 (Linux; Android 14) Mobile".to_string()),
+                tab: true,
+            },
+            ..launch
+        };
+        assert_eq!(
+            args(&launch),
+            vec![
+                "--class=tarantula-gmail",
+                "--name=tarantula-gmail",
+                "--window-size=1200,800",
+                "--window-position=100,50",
+                "--force-device-scale-factor=1.25",
+                "--lang=de-DE",
+                "--user-agent=Mozilla/5.0 (Linux; Android 14) Mobile",
+                "https://mail.google.com",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_network_args() {
+        let launch = Launch {
+            url: "https://intranet.corp".to_string(),
+            class: "tarantula-intranet".to_string(),
+            profile_path: None,
+            minimized: false,
+            options: LaunchOptions::default(),
+            network: NetworkOptions {
+                proxy: Some("socks5://localhost:1080".to_string()),
+                proxy_bypass: vec!["<local>".to_string(), "*.example.com".to_string()],
+                host_rules: Some("MAP *.corp 10.0.0.53, EXCLUDE localhost".to_string()),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            args(&launch),
+            vec![
+                "--class=tarantula-intranet",
+                "--name=tarantula-intranet",
+                "--proxy-server=socks5://localhost:1080",
+                "--proxy-bypass-list=<local>;*.example.com",
+                "--host-resolver-rules=MAP *.corp 10.0.0.53, EXCLUDE localhost",
+                "--app=https://intranet.corp",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_local_state() {
+        use crate::domain::app::SecureDns;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Local State");
+        std::fs::write(&path, r#"{"browser":{"enabled_labs_experiments":[]}}"#).unwrap();
+
+        let network = NetworkOptions {
+            secure_dns: Some(SecureDns::Secure),
+            doh_template: Some("https://dns.example.com/dns-query{?dns}".to_string()),
+            ..Default::default()
+        };
+        write_local_state(dir.path(), &network).unwrap();
+
+        let state: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(state["dns_over_https"]["mode"], "secure");
+        assert_eq!(
+            state["dns_over_https"]["templates"],
+            "https://dns.example.com/dns-query{?dns}"
+        );
+        assert!(state["browser"]["enabled_labs_experiments"].is_array());
+    }
+}
@@ -0,0 +1,180 @@
+use std::path::PathBuf;
+
+use super::{Launch, RunError};
+use crate::domain::app::App;
+
+// The embedded engine opens the app in a window of the tarantula UI, a WebKitGTK
+// webview, so no browser is needed.
+
+// The UI binary the app opens in. GTK names the windows after the program, so it's
+// started under the app's window class, see CommandLine::arg0.
+pub fn program(ui_binary: Option<fn() -> Option<PathBuf>>) -> Result<PathBuf, RunError> {
+    ui_binary.and_then(|find| find()).ok_or_else(|| {
+        RunError::LaunchFailed(
+            "The embedded engine needs the tarantula UI, run the app with the tarantula command"
+                .to_string(),
+        )
+    })
+}
+
+// Arguments of the UI's single app mode.
+pub fn args(launch: &Launch, app: &App) -> Result<Vec<String>, RunError> {
+    let profile_path = launch.profile_path.as_ref().ok_or_else(|| {
+        RunError::LaunchFailed("The embedded engine needs a profile for every app".to_string())
+    })?;
+
+    let mut args = vec![
+        "--web-app".to_string(),
+        app.name.clone(),
+        "--url".to_string(),
+        launch.url.clone(),
+        "--data-dir".to_string(),
+        profile_path.display().to_string(),
+    ];
+    if let Some(geometry) = &launch.options.geometry {
+        args.extend(["--geometry".to_string(), geometry.to_string()]);
+    }
+    if let Some(scale) = launch.options.scale {
+        args.extend(["--scale".to_string(), scale.to_string()]);
+    }
+    if let Some(user_agent) = &launch.options.user_agent {
+        args.extend(["--user-agent".to_string(), user_agent.clone()]);
+    }
+    Ok(args)
+}
+
+// Links that leave the app open in the default browser. Pages on other subdomains of
+// the app's domain stay in the app, which keeps sign-in pages like accounts.google.com
+// for mail.google.com working.
+pub fn is_external(app_url: &url::Url, target: &url::Url) -> bool {
+    match target.scheme() {
+        "http" | "https" => {}
+        "mailto" | "tel" => return true,
+        // about:blank, blob: and data: URLs belong to the page
+        _ => return false,
+    }
+
+    match (site(app_url), site(target)) {
+        (Some(app_site), Some(target_site)) => app_site != target_site,
+        _ => true,
+    }
+}
+
+// The registrable part of the host from the public suffix list, e.g. google.com for
+// mail.google.com and bbc.co.uk for www.bbc.co.uk. Hosts without one, like IP
+// addresses and localhost, are their own site.
+fn site(url: &url::Url) -> Option<String> {
+    let host = url.host_str()?;
+    if url
+        .host()
+        .is_some_and(|h| !matches!(h, url::Host::Domain(_)))
+    {
+        return Some(host.to_string());
+    }
+    let site = psl::domain_str(host).unwrap_or(host);
+    Some(site.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        let app = App {
+            name: "Gmail".to_string(),
+            url: "https://mail.google.com".to_string(),
+            ..Default::default()
+        };
+        let launch = Launch {
+            url: "https://mail.google.com/#inbox".to_string(),
+            class: app.window_class(),
+            profile_path: Some(PathBuf::from("/data/embedded-profiles/gmail")),
+            minimized: false,
+            options: Default::default(),
+            network: Default::default(),
+        };
+        assert_eq!(
+            args(&launch, &app).unwrap(),
+            vec![
+                "--web-app",
+                "Gmail",
+                "--url",
+                "https://mail.google.com/#inbox",
+                "--data-dir",
+                "/data/embedded-profiles/gmail",
+            ]
+        );
+        assert!(program(None).is_err());
+        assert!(program(Some(|| None)).is_err());
+        assert_eq!(
+            program(Some(|| Some(PathBuf::from("/cache/tarantula/ui")))).unwrap(),
+            PathBuf::from("/cache/tarantula/ui")
+        );
+
+        let launch = Launch {
+            options: crate::domain::app::LaunchOptions {
+                geometry: Some("400x800".parse().unwrap()),
+                scale: Some(2.0),
+                user_agent: Some("Mobile".to_string()),
+                ..Default::default()
+            },
+            ..launch
+        };
+        assert_eq!(
+            args(&launch, &app).unwrap()[6..],
+            [
+                "--geometry",
+                "400x800",
+                "--scale",
+                "2",
+                "--user-agent",
+                "Mobile"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_external() {
+        let app_url = url::Url::parse("https://mail.google.com/mail/u/0/").unwrap();
+        let external = |target: &str| is_external(&app_url, &url::Url::parse(target).unwrap());
+
+        assert!(!external("https://mail.google.com/mail/u/0/#inbox"));
+        assert!(!external("https://accounts.google.com/signin"));
+        assert!(!external("about:blank"));
+        assert!(external("https://example.com/"));
+        assert!(external("mailto:someone@example.com"));
+
+        let app_url = url::Url::parse("https://www.bbc.co.uk/news").unwrap();
+        let external = |target: &str| is_external(&app_url, &url::Url::parse(target).unwrap());
+        assert!(!external("https://account.bbc.co.uk/signin"));
+        assert!(external("https://www.amazon.co.uk/"));
+
+        // short second levels that aren't public suffixes
+        let app_url = url::Url::parse("https://app.hey.io/").unwrap();
+        let external = |target: &str| is_external(&app_url, &url::Url::parse(target).unwrap());
+        assert!(!external("https://hey.io/sign_in"));
+        assert!(external("https://other.io/"));
+
+        let app_url = url::Url::parse("https://grok.x.ai/").unwrap();
+        let external = |target: &str| is_external(&app_url, &url::Url::parse(target).unwrap());
+        assert!(!external("https://accounts.x.ai/sign-in"));
+        assert!(external("https://y.ai/"));
+
+        let local = url::Url::parse("http://localhost:3000/").unwrap();
+        assert!(!is_external(
+            &local,
+            &url::Url::parse("http://localhost:3000/login").unwrap()
+        ));
+
+        let local = url::Url::parse("http://192.168.1.10:8080/").unwrap();
+        assert!(!is_external(
+            &local,
+            &url::Url::parse("http://192.168.1.10:8080/settings").unwrap()
+        ));
+        assert!(is_external(
+            &local,
+            &url::Url::parse("http://192.168.1.11/").unwrap()
+        ));
+    }
+}
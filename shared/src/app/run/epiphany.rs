@@ -0,0 +1,134 @@
+use std::path::Path;
+
+use super::{Launch, RunError};
+use crate::app::config::Config;
+use crate::domain::app::App;
+use crate::domain::app_name::AppName;
+use crate::infra::desktop_entry::{DesktopFile, MAIN_GROUP, quote_exec};
+
+// Epiphany runs web apps from a profile directory named after the app id that holds
+// a .app marker and the app's desktop file, which it reads the name and icon from.
+pub fn args(launch: &Launch) -> Result<Vec<String>, RunError> {
+    let profile_path = launch.profile_path.as_ref().ok_or_else(|| {
+        RunError::LaunchFailed("Epiphany needs a profile for every app".to_string())
+    })?;
+
+    Ok(vec![
+        "--application-mode".to_string(),
+        format!("--profile={}", profile_path.display()),
+        launch.url.clone(),
+    ])
+}
+
+pub fn write_profile(profile_path: &Path, app: &App, config: &Config) -> Result<(), RunError> {
+    let name = AppName::parse(&app.name).map_err(|e| RunError::LaunchFailed(e.to_string()))?;
+    let id = app.epiphany_id();
+    std::fs::create_dir_all(profile_path).map_err(RunError::Io)?;
+    std::fs::write(profile_path.join(".app"), "").map_err(RunError::Io)?;
+
+    // Epiphany's own web app list launches the app through tarantula as well
+    let mut file = DesktopFile::new();
+    let entry = file.group_mut(MAIN_GROUP);
+    entry.set("Name", name.as_str());
+    entry.set("Exec", &quote_exec(&[config.launcher(), &name.slug()]));
+    entry.set("Type", "Application");
+    entry.set("StartupWMClass", &id);
+    entry.set("StartupNotify", "true");
+    entry.set("Terminal", "false");
+    // listed in launchers through the desktop entry tarantula writes
+    entry.set("NoDisplay", "true");
+    if let Some(icon) = &app.icon {
+        entry.set("Icon", icon);
+    }
+    file.write(&profile_path.join(format!("{}.desktop", id)))
+        .map_err(|e| RunError::Io(std::io::Error::other(format!("{:?}", e))))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::app::Engine;
+
+    #[test]
+    fn test_args() {
+        let (config, dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        let app = App {
+            name: "Proton Mail".to_string(),
+            url: "https://mail.proton.me".to_string(),
+            icon: Some("/icons/proton.png".to_string()),
+            engine: Some(Engine::Epiphany),
+            ..Default::default()
+        };
+        let profile_path = dir
+            .path()
+            .join("org.gnome.Epiphany.WebApp_tarantula_proton_mail");
+        let launch = Launch {
+            url: app.url.clone(),
+            class: app.window_class(),
+            profile_path: Some(profile_path.clone()),
+            minimized: false,
+            options: Default::default(),
+            network: Default::default(),
+        };
+
+        assert_eq!(
+            args(&launch).unwrap(),
+            vec![
+                "--application-mode".to_string(),
+                format!("--profile={}", profile_path.display()),
+                "https://mail.proton.me".to_string(),
+            ]
+        );
+        assert_eq!(
+            app.window_class(),
+            "org.gnome.Epiphany.WebApp_tarantula_proton_mail"
+        );
+        write_profile(&profile_path, &app, &config).unwrap();
+        assert!(profile_path.join(".app").exists());
+
+        let file = DesktopFile::read(
+            &profile_path.join("org.gnome.Epiphany.WebApp_tarantula_proton_mail.desktop"),
+        )
+        .unwrap();
+        let entry = file.group(MAIN_GROUP).unwrap();
+        assert_eq!(entry.get("Name").as_deref(), Some("Proton Mail"));
+        assert_eq!(entry.get("Exec").as_deref(), Some("tarantula proton_mail"));
+        assert_eq!(
+            entry.get("StartupWMClass").as_deref(),
+            Some("org.gnome.Epiphany.WebApp_tarantula_proton_mail")
+        );
+        assert_eq!(entry.get("Icon").as_deref(), Some("/icons/proton.png"));
+    }
+
+    #[test]
+    fn test_epiphany_id() {
+        let id = |name: &str| {
+            App {
+                name: name.to_string(),
+                ..Default::default()
+            }
+            .epiphany_id()
+        };
+
+        assert_eq!(id("Gmail"), "org.gnome.Epiphany.WebApp_tarantula_gmail");
+        // apps with different slugs get profiles and window classes of their own
+        for (a, b) in [
+            ("Mail-Work", "Mail Work"),
+            ("Почта", "Карта"),
+            ("Ko-fi", "Ko.fi"),
+        ] {
+            assert_ne!(id(a), id(b), "{} and {}", a, b);
+        }
+        assert_eq!(
+            id("Mail-Work"),
+            "org.gnome.Epiphany.WebApp_tarantula_mailX2DXwork"
+        );
+        assert!(
+            id("Почта")
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        );
+    }
+}
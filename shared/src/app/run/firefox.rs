@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use super::{Launch, RunError};
+use crate::domain::app::LaunchOptions;
+
+// Firefox has no app mode, so every app gets a profile of its own whose
+// userChrome.css hides the tab strip and toolbars.
+
+// written on every launch, so changes made to them are lost
+const USER_JS: &str = r#"// Written by tarantula, changes are overwritten on the next launch
+user_pref("toolkit.legacyUserProfileCustomizations.stylesheets", true);
+user_pref("browser.shell.checkDefaultBrowser", false);
+user_pref("browser.aboutwelcome.enabled", false);
+user_pref("browser.startup.homepage_override.mstone", "ignore");
+user_pref("browser.sessionstore.resume_from_crash", false);
+user_pref("browser.tabs.warnOnClose", false);
+user_pref("browser.toolbars.bookmarks.visibility", "never");
+user_pref("datareporting.policy.dataSubmissionPolicyBypassNotification", true);
+"#;
+
+const USER_CHROME_HEADER: &str =
+    "/* Written by tarantula, changes are overwritten on the next launch */\n";
+
+const USER_CHROME_CSS: &str = r#"#TabsToolbar,
+#nav-bar,
+#PersonalToolbar,
+#titlebar,
+#sidebar-box,
+#sidebar-splitter {
+  visibility: collapse !important;
+}
+"#;
+
+pub fn args(launch: &Launch) -> Result<Vec<String>, RunError> {
+    let profile_path = launch.profile_path.as_ref().ok_or_else(|| {
+        RunError::LaunchFailed("Firefox needs a profile for every app".to_string())
+    })?;
+
+    let mut args = vec![
+        "--no-remote".to_string(),
+        "--profile".to_string(),
+        profile_path.display().to_string(),
+        "--class".to_string(),
+        launch.class.clone(),
+        "--name".to_string(),
+        launch.class.clone(),
+    ];
+    if !launch.options.tab {
+        args.push("--new-window".to_string());
+    }
+    args.push(launch.url.clone());
+    Ok(args)
+}
+
+// Writes the prefs and the userChrome.css that hides the browser's toolbars.
+pub fn write_profile(profile_path: &Path, options: &LaunchOptions) -> Result<(), std::io::Error> {
+    let chrome = profile_path.join("chrome");
+    std::fs::create_dir_all(&chrome)?;
+
+    let mut user_js = USER_JS.to_string();
+    // JSON strings are valid JavaScript strings
+    let mut pref = |name: &str, value: String| {
+        user_js.push_str(&format!("user_pref(\"{}\", {});\n", name, value));
+    };
+    if let Some(scale) = options.scale {
+        pref("layout.css.devPixelsPerPx", format!("\"{}\"", scale));
+    }
+    if let Some(language) = &options.language {
+        pref(
+            "intl.accept_languages",
+            serde_json::json!(language).to_string(),
+        );
+    }
+    if let Some(user_agent) = &options.user_agent {
+        pref(
+            "general.useragent.override",
+            serde_json::json!(user_agent).to_string(),
+        );
+    }
+    std::fs::write(profile_path.join("user.js"), user_js)?;
+
+    // tabs keep the toolbars
+    let mut user_chrome = USER_CHROME_HEADER.to_string();
+    if !options.tab {
+        user_chrome.push_str(USER_CHROME_CSS);
+    }
+    std::fs::write(chrome.join("userChrome.css"), user_chrome)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile_path = dir.path().join("gmail");
+        let launch = Launch {
+            url: "https://mail.google.com".to_string(),
+            class: "tarantula-gmail".to_string(),
+            profile_path: Some(profile_path.clone()),
+            minimized: false,
+            options: LaunchOptions::default(),
+            network: Default::default(),
+        };
+
+        assert_eq!(
+            args(&launch).unwrap(),
+            vec![
+                "--no-remote".to_string(),
+                "--profile".to_string(),
+                profile_path.display().to_string(),
+                "--class".to_string(),
+                "tarantula-gmail".to_string(),
+                "--name".to_string(),
+                "tarantula-gmail".to_string(),
+                "--new-window".to_string(),
+                "https://mail.google.com".to_string(),
+            ]
+        );
+        write_profile(&profile_path, &launch.options).unwrap();
+        let user_js = std::fs::read_to_string(profile_path.join("user.js")).unwrap();
+        assert!(
+            user_js.contains(
+                "user_pref(\"toolkit.legacyUserProfileCustomizations.stylesheets\", true);"
+            )
+        );
+        let user_chrome =
+            std::fs::read_to_string(profile_path.join("chrome/userChrome.css")).unwrap();
+        assert!(user_chrome.contains("#TabsToolbar"));
+
+        let tab = Launch {
+            options: LaunchOptions {
+                scale: Some(1.5),
+                language: Some("de-DE".to_string()),
+                user_agent: Some("Agent \"1\"".to_string()),
+                tab: true,
+                ..Default::default()
+            },
+            ..launch.clone()
+        };
+        assert_eq!(
+            args(&tab).unwrap().last().map(String::as_str),
+            Some("https://mail.google.com")
+        );
+        assert!(!args(&tab).unwrap().contains(&"--new-window".to_string()));
+        write_profile(&profile_path, &tab.options).unwrap();
+        let user_js = std::fs::read_to_string(profile_path.join("user.js")).unwrap();
+        assert!(user_js.contains("user_pref(\"layout.css.devPixelsPerPx\", \"1.5\");\n"));
+        assert!(user_js.contains("user_pref(\"intl.accept_languages\", \"de-DE\");\n"));
+        assert!(
+            user_js.contains("user_pref(\"general.useragent.override\", \"Agent \\\"1\\\"\");\n")
+        );
+        let user_chrome =
+            std::fs::read_to_string(profile_path.join("chrome/userChrome.css")).unwrap();
+        assert!(!user_chrome.contains("#TabsToolbar"));
+
+        let launch = Launch {
+            profile_path: None,
+            ..launch
+        };
+        assert!(args(&launch).is_err());
+    }
+}
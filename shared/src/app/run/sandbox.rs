@@ -0,0 +1,282 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::domain::app::SandboxMode;
+use crate::utils;
+
+// A sandboxed browser sees the system's directories read-only and, of the user's
+// files, only the app's profile and the downloads folder. The display socket and
+// the network stay available, sound and D-Bus don't.
+
+const FIREJAIL_HEADER: &str =
+    "# Written by tarantula, changes are overwritten on the next launch\n";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tool {
+    Bubblewrap,
+    Firejail,
+}
+
+impl Tool {
+    fn command(&self) -> &'static str {
+        match self {
+            Tool::Bubblewrap => "bwrap",
+            Tool::Firejail => "firejail",
+        }
+    }
+}
+
+impl std::fmt::Display for Tool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Tool::Bubblewrap => "bubblewrap",
+            Tool::Firejail => "firejail",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// The installed tool for the mode and its path, bubblewrap first for auto.
+pub fn find_tool(mode: SandboxMode) -> Option<(Tool, PathBuf)> {
+    find_tool_in(mode, &std::env::var_os("PATH")?)
+}
+
+// Like find_tool, but searches the given $PATH value.
+fn find_tool_in(mode: SandboxMode, paths: &OsStr) -> Option<(Tool, PathBuf)> {
+    let tools: &[Tool] = match mode {
+        SandboxMode::Auto => &[Tool::Bubblewrap, Tool::Firejail],
+        SandboxMode::Bubblewrap => &[Tool::Bubblewrap],
+        SandboxMode::Firejail => &[Tool::Firejail],
+    };
+    tools
+        .iter()
+        .find_map(|tool| Some((*tool, utils::find_in(tool.command(), paths)?)))
+}
+
+// What the sandbox exposes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exposure {
+    pub home: PathBuf,
+    pub profile_path: PathBuf,
+    pub downloads: PathBuf,
+    // where the browser is installed when that's outside /usr, e.g. /opt/google/chrome
+    pub program_dir: Option<PathBuf>,
+    // $XDG_RUNTIME_DIR/$WAYLAND_DISPLAY
+    pub wayland_socket: Option<PathBuf>,
+    // cookie file X11 clients authenticate with
+    pub xauthority: Option<PathBuf>,
+}
+
+impl Exposure {
+    pub fn from_env(profile_path: &Path, program: &str) -> Option<Self> {
+        let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty());
+        let home = dirs::home_dir()?;
+        let downloads = dirs::download_dir().unwrap_or(home.join("Downloads"));
+        // symlinks like /usr/bin/google-chrome point to the real installation
+        let program_dir = utils::find_in_path(program)
+            .and_then(|path| path.canonicalize().ok())
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            .filter(|dir| !dir.starts_with("/usr"));
+        let wayland_socket = match (var("XDG_RUNTIME_DIR"), var("WAYLAND_DISPLAY")) {
+            (Some(runtime_dir), Some(display)) => Some(PathBuf::from(runtime_dir).join(display)),
+            _ => None,
+        };
+
+        Some(Exposure {
+            home,
+            profile_path: profile_path.to_path_buf(),
+            downloads,
+            program_dir,
+            wayland_socket,
+            xauthority: var("XAUTHORITY").map(PathBuf::from),
+        })
+    }
+}
+
+// Arguments of bwrap that build the app's file system from an empty root, mounts
+// below a directory have to come after it.
+pub fn bwrap_args(exposure: &Exposure) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    let mut mount = |option: &str, path: &Path| {
+        let path = path.display().to_string();
+        match option {
+            "--proc" | "--dev" | "--tmpfs" => args.extend([option.to_string(), path]),
+            _ => args.extend([option.to_string(), path.clone(), path]),
+        }
+    };
+
+    mount("--ro-bind", Path::new("/usr"));
+    for dir in ["/bin", "/sbin", "/lib", "/lib64", "/lib32", "/sys"] {
+        mount("--ro-bind-try", Path::new(dir));
+    }
+    mount("--ro-bind", Path::new("/etc"));
+    // /etc/resolv.conf links here with systemd-resolved
+    mount("--ro-bind-try", Path::new("/run/systemd/resolve"));
+    mount("--proc", Path::new("/proc"));
+    mount("--dev", Path::new("/dev"));
+    mount("--dev-bind-try", Path::new("/dev/dri"));
+    mount("--tmpfs", Path::new("/tmp"));
+    mount("--ro-bind-try", Path::new("/tmp/.X11-unix"));
+    mount("--tmpfs", &exposure.home);
+    mount("--bind", &exposure.profile_path);
+    mount("--bind", &exposure.downloads);
+    if let Some(program_dir) = &exposure.program_dir {
+        mount("--ro-bind", program_dir);
+    }
+    if let Some(wayland_socket) = &exposure.wayland_socket {
+        mount("--ro-bind-try", wayland_socket);
+    }
+    if let Some(xauthority) = &exposure.xauthority {
+        mount("--ro-bind-try", xauthority);
+    }
+
+    args.extend(
+        [
+            "--unshare-all",
+            "--share-net",
+            "--die-with-parent",
+            "--new-session",
+        ]
+        .map(String::from),
+    );
+    args
+}
+
+// Firejail profile that hides the home directory but for the app's files.
+pub fn firejail_profile(exposure: &Exposure) -> String {
+    let mut profile = FIREJAIL_HEADER.to_string();
+    let mut whitelist = |path: &Path| {
+        // firejail only whitelists paths in the home directory and a few others
+        if path.starts_with(&exposure.home) {
+            profile.push_str(&format!("whitelist {}\n", path.display()));
+        }
+    };
+    whitelist(&exposure.profile_path);
+    whitelist(&exposure.downloads);
+    if let Some(program_dir) = &exposure.program_dir {
+        whitelist(program_dir);
+    }
+    if let Some(xauthority) = &exposure.xauthority {
+        whitelist(xauthority);
+    }
+    profile.push_str(
+        "private-dev\nprivate-tmp\nnogroups\nnosound\ndbus-user none\ndbus-system none\n",
+    );
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exposure() -> Exposure {
+        Exposure {
+            home: PathBuf::from("/home/me"),
+            profile_path: PathBuf::from("/home/me/.local/share/tarantula/profiles/gmail"),
+            downloads: PathBuf::from("/home/me/Downloads"),
+            program_dir: Some(PathBuf::from("/opt/google/chrome")),
+            wayland_socket: Some(PathBuf::from("/run/user/1000/wayland-0")),
+            xauthority: None,
+        }
+    }
+
+    #[test]
+    fn test_bwrap_args() {
+        let args = bwrap_args(&exposure());
+        let position = |arg: &str| args.iter().position(|a| a == arg).unwrap();
+
+        assert_eq!(args[..3], ["--ro-bind", "/usr", "/usr"]);
+        assert!(args.windows(3).any(|w| w
+            == [
+                "--bind",
+                "/home/me/.local/share/tarantula/profiles/gmail",
+                "/home/me/.local/share/tarantula/profiles/gmail"
+            ]));
+        assert!(
+            args.windows(3)
+                .any(|w| w == ["--ro-bind", "/opt/google/chrome", "/opt/google/chrome"])
+        );
+        assert!(args.windows(3).any(|w| w
+            == [
+                "--ro-bind-try",
+                "/run/user/1000/wayland-0",
+                "/run/user/1000/wayland-0"
+            ]));
+        // the home directory is empty but for the binds after it
+        assert!(position("/home/me") < position("/home/me/Downloads"));
+        assert!(position("/tmp") < position("/tmp/.X11-unix"));
+        assert!(args.ends_with(&[
+            "--unshare-all".to_string(),
+            "--share-net".to_string(),
+            "--die-with-parent".to_string(),
+            "--new-session".to_string(),
+        ]));
+    }
+
+    #[test]
+    fn test_firejail_profile() {
+        let profile = firejail_profile(&exposure());
+        assert!(profile.contains("whitelist /home/me/.local/share/tarantula/profiles/gmail\n"));
+        assert!(profile.contains("whitelist /home/me/Downloads\n"));
+        // outside the home directory, which firejail doesn't hide
+        assert!(!profile.contains("/opt/google/chrome"));
+        assert!(profile.contains("private-dev\n"));
+    }
+
+    #[test]
+    fn test_find_tool() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bwrap_dir = tempfile::tempdir().unwrap();
+        let firejail_dir = tempfile::tempdir().unwrap();
+        let empty_dir = tempfile::tempdir().unwrap();
+        for (dir, command) in [(&bwrap_dir, "bwrap"), (&firejail_dir, "firejail")] {
+            let path = dir.path().join(command);
+            std::fs::write(&path, "").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let bwrap = bwrap_dir.path().join("bwrap");
+        let firejail = firejail_dir.path().join("firejail");
+        let paths = |dirs: &[&tempfile::TempDir]| {
+            std::env::join_paths(dirs.iter().map(|dir| dir.path())).unwrap()
+        };
+
+        // auto prefers bubblewrap wherever it is on $PATH
+        let both = paths(&[&firejail_dir, &bwrap_dir]);
+        assert_eq!(
+            find_tool_in(SandboxMode::Auto, &both),
+            Some((Tool::Bubblewrap, bwrap.clone()))
+        );
+        assert_eq!(
+            find_tool_in(SandboxMode::Auto, &paths(&[&firejail_dir])),
+            Some((Tool::Firejail, firejail.clone()))
+        );
+
+        // an explicit mode only takes its own tool
+        assert_eq!(
+            find_tool_in(SandboxMode::Bubblewrap, &both),
+            Some((Tool::Bubblewrap, bwrap))
+        );
+        assert_eq!(
+            find_tool_in(SandboxMode::Firejail, &both),
+            Some((Tool::Firejail, firejail))
+        );
+        assert_eq!(
+            find_tool_in(SandboxMode::Bubblewrap, &paths(&[&firejail_dir])),
+            None
+        );
+        assert_eq!(
+            find_tool_in(SandboxMode::Firejail, &paths(&[&bwrap_dir])),
+            None
+        );
+
+        let empty = paths(&[&empty_dir]);
+        for mode in [
+            SandboxMode::Auto,
+            SandboxMode::Bubblewrap,
+            SandboxMode::Firejail,
+        ] {
+            assert_eq!(find_tool_in(mode, &empty), None);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use crate::domain::app::SessionFlags;
+use crate::infra::desktop_entry;
+
+// Chromium runs under XWayland unless told otherwise, which makes its windows blurry
+// on scaled outputs, and draws no window decorations of its own on Wayland.
+pub const WAYLAND_DEFAULTS: [&str; 2] = [
+    "--ozone-platform-hint=auto",
+    "--enable-features=WaylandWindowDecorations",
+];
+
+pub const X11_DEFAULTS: [&str; 0] = [];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Session {
+    Wayland,
+    X11,
+}
+
+impl std::fmt::Display for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Session::Wayland => "wayland",
+            Session::X11 => "x11",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Where the flags of a session come from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    App,
+    Config,
+    Default,
+}
+
+// The flags chosen for the current session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Choice {
+    pub session: Session,
+    pub flags: Vec<String>,
+    pub source: Source,
+}
+
+impl std::fmt::Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self.source {
+            Source::App => "the app's settings",
+            Source::Config => "the config",
+            Source::Default => "the defaults",
+        };
+        write!(f, "{} session, flags from {}:", self.session, source)?;
+        if self.flags.is_empty() {
+            write!(f, " none")?;
+        }
+        for flag in &self.flags {
+            write!(f, " {}", flag)?;
+        }
+        Ok(())
+    }
+}
+
+// The session of the display the app opens on. $XDG_SESSION_TYPE is set by the login
+// manager, the display variables cover sessions started without one, e.g. from a tty.
+pub fn detect() -> Option<Session> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    detect_from(
+        var("XDG_SESSION_TYPE").as_deref(),
+        var("WAYLAND_DISPLAY").is_some(),
+        var("DISPLAY").is_some(),
+    )
+}
+
+fn detect_from(
+    session_type: Option<&str>,
+    wayland_display: bool,
+    display: bool,
+) -> Option<Session> {
+    match session_type {
+        Some("wayland") => Some(Session::Wayland),
+        Some("x11") => Some(Session::X11),
+        _ if wayland_display => Some(Session::Wayland),
+        _ if display => Some(Session::X11),
+        _ => None,
+    }
+}
+
+// The app's flags for the session, then the config's, then the defaults.
+pub fn choose(session: Session, app: &SessionFlags, config: &SessionFlags) -> Choice {
+    let pick = |flags: &SessionFlags| match session {
+        Session::Wayland => flags.wayland.clone(),
+        Session::X11 => flags.x11.clone(),
+    };
+    let (flags, source) = match (pick(app), pick(config)) {
+        (Some(flags), _) => (flags, Source::App),
+        (None, Some(flags)) => (flags, Source::Config),
+        (None, None) => {
+            let defaults: &[&str] = match session {
+                Session::Wayland => &WAYLAND_DEFAULTS,
+                Session::X11 => &X11_DEFAULTS,
+            };
+            (
+                defaults.iter().map(|f| f.to_string()).collect(),
+                Source::Default,
+            )
+        }
+    };
+    Choice {
+        session,
+        flags,
+        source,
+    }
+}
+
+// Parses flags written on one line, "default" going back to the defaults.
+pub fn parse_flags(value: &str) -> Result<Option<Vec<String>>, String> {
+    if value == "default" {
+        return Ok(None);
+    }
+    let flags = desktop_entry::parse_exec(value)
+        .map_err(|_| format!("Invalid flags {}, a quote isn't closed", value))?;
+    if let Some(flag) = flags.iter().find(|f| !f.starts_with('-')) {
+        return Err(format!(
+            "Invalid flag {}, expected flags like --ozone-platform-hint=auto",
+            flag
+        ));
+    }
+    Ok(Some(flags))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            detect_from(Some("wayland"), false, true),
+            Some(Session::Wayland)
+        );
+        assert_eq!(detect_from(Some("x11"), true, true), Some(Session::X11));
+        assert_eq!(detect_from(Some("tty"), true, true), Some(Session::Wayland));
+        assert_eq!(detect_from(None, false, true), Some(Session::X11));
+        assert_eq!(detect_from(None, false, false), None);
+    }
+
+    #[test]
+    fn test_choose() {
+        let config = SessionFlags {
+            wayland: Some(vec!["--disable-gpu-compositing".to_string()]),
+            x11: None,
+        };
+        let app = SessionFlags {
+            wayland: None,
+            x11: Some(Vec::new()),
+        };
+
+        let choice = choose(Session::Wayland, &app, &config);
+        assert_eq!(choice.flags, vec!["--disable-gpu-compositing"]);
+        assert_eq!(choice.source, Source::Config);
+
+        let choice = choose(Session::X11, &app, &config);
+        assert!(choice.flags.is_empty());
+        assert_eq!(choice.source, Source::App);
+
+        let choice = choose(
+            Session::Wayland,
+            &SessionFlags::default(),
+            &SessionFlags::default(),
+        );
+        assert_eq!(choice.flags, WAYLAND_DEFAULTS);
+        assert_eq!(choice.source, Source::Default);
+        assert_eq!(
+            choice.to_string(),
+            "wayland session, flags from the defaults: --ozone-platform-hint=auto --enable-features=WaylandWindowDecorations"
+        );
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        assert_eq!(parse_flags("default").unwrap(), None);
+        assert_eq!(parse_flags("").unwrap(), Some(Vec::new()));
+        assert_eq!(
+            parse_flags("--ozone-platform=wayland --disable-gpu-compositing").unwrap(),
+            Some(vec![
+                "--ozone-platform=wayland".to_string(),
+                "--disable-gpu-compositing".to_string()
+            ])
+        );
+        assert!(parse_flags("wayland").is_err());
+    }
+}
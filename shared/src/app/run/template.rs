@@ -0,0 +1,153 @@
+use crate::domain::app::CommandTemplate;
+use crate::infra::desktop_entry;
+
+// Launch templates are split into words like desktop entry Exec lines, with double
+// quotes keeping spaces. Each placeholder is a word of its own that expands to any
+// number of arguments, so nothing goes through a shell.
+
+pub const DEFAULT: &str = "{wrapper} {browser} {engine_args} {extra_args}";
+
+const PLACEHOLDERS: [&str; 4] = ["{wrapper}", "{browser}", "{engine_args}", "{extra_args}"];
+
+// Expands the template into the words of the command, the first being the program.
+pub fn expand(
+    command: &CommandTemplate,
+    browser: &[String],
+    engine_args: &[String],
+) -> Result<Vec<String>, String> {
+    let template = command.template.as_deref().unwrap_or(DEFAULT);
+    let wrapper = split(command.wrapper.as_deref().unwrap_or_default())?;
+    let extra_args = split(command.extra_args.as_deref().unwrap_or_default())?;
+
+    let mut words = Vec::new();
+    for word in split(template)? {
+        match word.as_str() {
+            "{wrapper}" => words.extend(wrapper.iter().cloned()),
+            "{browser}" => words.extend(browser.iter().cloned()),
+            "{engine_args}" => words.extend(engine_args.iter().cloned()),
+            "{extra_args}" => words.extend(extra_args.iter().cloned()),
+            _ if PLACEHOLDERS.iter().any(|p| word.contains(p)) => {
+                return Err(format!(
+                    "Invalid launch template {}, placeholders must be words of their own",
+                    template
+                ));
+            }
+            _ if word.starts_with('{') && word.ends_with('}') => {
+                return Err(format!(
+                    "Unknown placeholder {} in the launch template, expected {}",
+                    word,
+                    PLACEHOLDERS.join(", ")
+                ));
+            }
+            _ => words.push(word),
+        }
+    }
+    Ok(words)
+}
+
+pub fn validate(command: &CommandTemplate) -> Result<(), String> {
+    let template = command.template.as_deref().unwrap_or(DEFAULT);
+    if split(template)?
+        .iter()
+        .filter(|w| *w == "{browser}")
+        .count()
+        != 1
+    {
+        return Err(format!(
+            "Invalid launch template {}, it needs {{browser}} once",
+            template
+        ));
+    }
+    expand(command, &[], &[])?;
+
+    if let Some(name) = command.env.keys().find(|name| {
+        name.is_empty()
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }) {
+        return Err(format!("Invalid environment variable name {}", name));
+    }
+    if let Some((name, _)) = command.env.iter().find(|(_, value)| value.contains('\0')) {
+        return Err(format!(
+            "The value of {} cannot contain NUL characters",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn split(command: &str) -> Result<Vec<String>, String> {
+    desktop_entry::parse_exec(command)
+        .map_err(|_| format!("Invalid command line {}, a quote isn't closed", command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let browser = vec![
+            "flatpak".to_string(),
+            "run".to_string(),
+            "org.chromium.Chromium".to_string(),
+        ];
+        let engine_args = vec!["--app=https://example.com".to_string()];
+
+        let command = CommandTemplate::default();
+        assert_eq!(
+            expand(&command, &browser, &engine_args).unwrap(),
+            vec![
+                "flatpak",
+                "run",
+                "org.chromium.Chromium",
+                "--app=https://example.com"
+            ]
+        );
+
+        let command = CommandTemplate {
+            template: Some(
+                "systemd-run --user --scope {wrapper} {browser} {extra_args} {engine_args}"
+                    .to_string(),
+            ),
+            wrapper: Some("prime-run".to_string()),
+            extra_args: Some("--enable-features=\"A B\" $HOME;".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            expand(&command, &browser[2..], &engine_args).unwrap(),
+            vec![
+                "systemd-run",
+                "--user",
+                "--scope",
+                "prime-run",
+                "org.chromium.Chromium",
+                "--enable-features=A B",
+                "$HOME;",
+                "--app=https://example.com",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let with_template = |template: &str| CommandTemplate {
+            template: Some(template.to_string()),
+            ..Default::default()
+        };
+        assert!(validate(&with_template("nice -n 10 {browser} {engine_args}")).is_ok());
+        assert!(validate(&with_template("{wrapper} {engine_args}")).is_err());
+        assert!(validate(&with_template("{browser} {browser}")).is_err());
+        assert!(validate(&with_template("{browser} --flag={extra_args}")).is_err());
+        assert!(validate(&with_template("{browser} {args}")).is_err());
+        assert!(validate(&with_template("\"{browser}")).is_err());
+
+        let mut command = CommandTemplate::default();
+        command
+            .env
+            .insert("MOZ_ENABLE_WAYLAND".to_string(), "1".to_string());
+        assert!(validate(&command).is_ok());
+        command.env.insert("NOT VALID".to_string(), "1".to_string());
+        assert!(validate(&command).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::app::config::Config;
+use crate::app::export::compositor;
+use crate::domain::app_name::AppName;
 use crate::infra::app_data;
-use crate::infra::desktop_data::remove_entry;
+use crate::infra::desktop_data::{remove_autostart_entry, remove_entry, sync_menu};
+use crate::infra::shim_data;
 
 #[derive(Debug)]
 pub enum UninstallError {
@@ -21,12 +24,30 @@ pub fn uninstall(app_name: &str, config: &Config) -> Result<(), UninstallError>
         }
     })?;
 
-    remove_entry(&app_name, config).map_err(|e| {
-        UninstallError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("{:?}", e),
-        ))
-    })?;
+    // names that fail validation never had files written for them
+    let name = AppName::parse(app_name).ok();
+    if let Some(name) = &name {
+        remove_entry(name, config).map_err(|e| {
+            UninstallError::Io(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("{:?}", e),
+            ))
+        })?;
+
+        remove_autostart_entry(name, config).map_err(|e| {
+            UninstallError::Io(std::io::Error::other(format!("{:?}", e)))
+        })?;
+
+        shim_data::remove_shim(name, config).map_err(|e| {
+            UninstallError::Io(std::io::Error::other(format!("{:?}", e)))
+        })?;
+    }
+
+    sync_menu(config)
+        .map_err(|e| UninstallError::Io(std::io::Error::other(format!("{:?}", e))))?;
+
+    compositor::sync(config)
+        .map_err(|e| UninstallError::Io(std::io::Error::other(format!("{:?}", e))))?;
 
     Ok(())
 }
@@ -44,7 +65,11 @@ mod tests {
         let url = "https://example.com";
 
         // First, install the app
-        install::install(name, url, &config).unwrap();
+        let options = install::InstallOptions {
+            shim: true,
+            ..Default::default()
+        };
+        install::install(name, url, &options, &config).unwrap();
 
         // Now, uninstall the app
         uninstall(name, &config).unwrap();
@@ -60,5 +85,11 @@ mod tests {
                 .exists()
                 == false
         );
+
+        // removes the shim
+        assert!(!config.bin_path.join("test-app").exists());
+
+        // removes the menu with the last app
+        assert!(!crate::infra::desktop_data::menu_path(&config).exists());
     }
 }
@@ -1,16 +1,66 @@
 use crate::app::config;
+use crate::app::export::compositor;
+use crate::app::run::{session, template};
+use crate::domain::app::{Engine, Geometry, SandboxMode, SecureDns, WindowSize};
+use crate::domain::app_name::AppName;
 use crate::infra::app_data;
 use crate::infra::desktop_data;
+use crate::infra::desktop_entry;
+use crate::infra::shim_data;
+use std::collections::BTreeMap;
 
-#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Default, serde::Deserialize, serde::Serialize)]
 pub struct UpdateOptions {
     pub name: Option<String>,
     pub url: Option<String>,
+    pub browser_path: Option<String>,
+    pub isolated: Option<bool>,
+    pub shim: Option<bool>,
+    // an empty workspace, size or hotkey removes it
+    pub workspace: Option<String>,
+    pub float: Option<bool>,
+    pub size: Option<String>,
+    // 1.0 removes the opacity rule
+    pub opacity: Option<f32>,
+    pub hotkey: Option<String>,
+    // replaces the app's tags, an empty list removes them
+    pub tags: Option<Vec<String>>,
+    // an empty description removes it, lists and localized names are replaced
+    pub description: Option<String>,
+    pub keywords: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub localized_names: Option<BTreeMap<String, String>>,
+    pub hidden: Option<bool>,
+    // an empty engine goes back to the one of the browser
+    pub engine: Option<String>,
+    // launch options, an empty geometry, language or user agent and a scale of 1.0 remove them
+    pub geometry: Option<String>,
+    pub scale: Option<f32>,
+    pub language: Option<String>,
+    pub user_agent: Option<String>,
+    pub tab: Option<bool>,
+    // network settings, empty values remove them and isolate the app unless isolated is set
+    pub proxy: Option<String>,
+    pub proxy_bypass: Option<Vec<String>>,
+    pub host_rules: Option<String>,
+    pub secure_dns: Option<String>,
+    pub doh_template: Option<String>,
+    // launch template, wrapper and extra arguments, empty ones remove them; env is replaced
+    pub launch_template: Option<String>,
+    pub wrapper: Option<String>,
+    pub extra_args: Option<String>,
+    pub env: Option<BTreeMap<String, String>>,
+    // flags on one line, "default" goes back to the config's or the default ones
+    pub wayland_flags: Option<String>,
+    pub x11_flags: Option<String>,
+    // bubblewrap, firejail or auto, an empty one turns the sandbox off
+    pub sandbox: Option<String>,
 }
 
 #[derive(Debug)]
 pub enum UpdateError {
     AppNotFound,
+    InvalidData(String),
     Io(std::io::Error),
 }
 
@@ -30,15 +80,226 @@ pub fn update(
         Some(app) => app,
         None => return Err(UpdateError::AppNotFound),
     };
+    let previous = app.clone();
 
     if let Some(name) = options.name.as_ref() {
         app.name = name.clone();
     }
+    let app_name =
+        AppName::parse(&app.name).map_err(|e| UpdateError::InvalidData(e.to_string()))?;
+    app.name = app_name.to_string();
 
     if let Some(url) = options.url.as_ref() {
         app.url = url.clone();
     }
 
+    if let Some(browser_path) = options.browser_path.as_ref() {
+        // an empty path goes back to the configured browser
+        app.browser_path = Some(browser_path.clone()).filter(|p| !p.is_empty());
+    }
+
+    if let Some(isolated) = options.isolated {
+        app.isolated = isolated;
+    }
+
+    if let Some(shim) = options.shim {
+        app.shim = shim;
+    }
+
+    if let Some(workspace) = options.workspace.as_ref() {
+        app.window.workspace = Some(workspace.clone()).filter(|w| !w.is_empty());
+        app.window.validate().map_err(UpdateError::InvalidData)?;
+    }
+
+    if let Some(float) = options.float {
+        app.window.float = float;
+    }
+
+    if let Some(size) = options.size.as_ref() {
+        app.window.size = match size.as_str() {
+            "" => None,
+            size => Some(
+                size.parse::<WindowSize>()
+                    .map_err(UpdateError::InvalidData)?,
+            ),
+        };
+    }
+
+    if let Some(opacity) = options.opacity {
+        app.window.opacity = Some(opacity).filter(|o| *o < 1.0);
+    }
+
+    if let Some(hotkey) = options.hotkey.as_ref() {
+        if !hotkey.is_empty() {
+            compositor::parse_hotkey(hotkey).map_err(UpdateError::InvalidData)?;
+        }
+        app.hotkey = Some(hotkey.clone()).filter(|h| !h.is_empty());
+    }
+
+    if let Some(tags) = options.tags.as_ref() {
+        app.tags = tags.iter().filter(|t| !t.is_empty()).cloned().collect();
+    }
+
+    if let Some(description) = options.description.as_ref() {
+        app.description = Some(description.clone()).filter(|d| !d.is_empty());
+    }
+
+    if let Some(keywords) = options.keywords.as_ref() {
+        app.keywords = keywords.iter().filter(|k| !k.is_empty()).cloned().collect();
+    }
+
+    if let Some(categories) = options.categories.as_ref() {
+        app.categories = categories
+            .iter()
+            .filter(|c| !c.is_empty())
+            .cloned()
+            .collect();
+    }
+
+    if let Some(localized_names) = options.localized_names.as_ref() {
+        if let Some(locale) = localized_names
+            .keys()
+            .find(|l| !desktop_entry::is_valid_locale(l))
+        {
+            return Err(UpdateError::InvalidData(format!(
+                "Invalid locale: {}",
+                locale
+            )));
+        }
+        app.localized_names = localized_names.clone();
+    }
+
+    if let Some(hidden) = options.hidden {
+        app.hidden = hidden;
+    }
+
+    if let Some(geometry) = options.geometry.as_ref() {
+        app.launch.geometry = match geometry.as_str() {
+            "" => None,
+            geometry => Some(
+                geometry
+                    .parse::<Geometry>()
+                    .map_err(UpdateError::InvalidData)?,
+            ),
+        };
+    }
+
+    if let Some(scale) = options.scale {
+        app.launch.scale = Some(scale).filter(|s| *s != 1.0);
+    }
+
+    if let Some(language) = options.language.as_ref() {
+        app.launch.language = Some(language.clone()).filter(|l| !l.is_empty());
+    }
+
+    if let Some(user_agent) = options.user_agent.as_ref() {
+        app.launch.user_agent = Some(user_agent.clone()).filter(|u| !u.is_empty());
+    }
+
+    if let Some(tab) = options.tab {
+        app.launch.tab = tab;
+    }
+
+    app.launch.validate().map_err(UpdateError::InvalidData)?;
+
+    if let Some(proxy) = options.proxy.as_ref() {
+        app.network.proxy = Some(proxy.clone()).filter(|p| !p.is_empty());
+    }
+
+    if let Some(proxy_bypass) = options.proxy_bypass.as_ref() {
+        app.network.proxy_bypass = proxy_bypass
+            .iter()
+            .filter(|h| !h.is_empty())
+            .cloned()
+            .collect();
+    }
+
+    if let Some(host_rules) = options.host_rules.as_ref() {
+        app.network.host_rules = Some(host_rules.clone()).filter(|r| !r.is_empty());
+    }
+
+    if let Some(secure_dns) = options.secure_dns.as_ref() {
+        app.network.secure_dns = match secure_dns.as_str() {
+            "" => None,
+            mode => Some(
+                mode.parse::<SecureDns>()
+                    .map_err(UpdateError::InvalidData)?,
+            ),
+        };
+    }
+
+    if let Some(doh_template) = options.doh_template.as_ref() {
+        app.network.doh_template = Some(doh_template.clone()).filter(|t| !t.is_empty());
+    }
+
+    if let Some(sandbox) = options.sandbox.as_ref() {
+        app.sandbox = match sandbox.as_str() {
+            "" => None,
+            sandbox => Some(
+                sandbox
+                    .parse::<SandboxMode>()
+                    .map_err(UpdateError::InvalidData)?,
+            ),
+        };
+    }
+
+    let isolation_changed = app.network != previous.network || app.sandbox != previous.sandbox;
+    if isolation_changed && app.needs_isolation() && options.isolated.is_none() {
+        app.isolated = true;
+    }
+    app.validate_isolation().map_err(UpdateError::InvalidData)?;
+
+    if let Some(launch_template) = options.launch_template.as_ref() {
+        app.command_template.template = Some(launch_template.clone()).filter(|t| !t.is_empty());
+    }
+
+    if let Some(wrapper) = options.wrapper.as_ref() {
+        app.command_template.wrapper = Some(wrapper.clone()).filter(|w| !w.is_empty());
+    }
+
+    if let Some(extra_args) = options.extra_args.as_ref() {
+        app.command_template.extra_args = Some(extra_args.clone()).filter(|a| !a.is_empty());
+    }
+
+    if let Some(env) = options.env.as_ref() {
+        app.command_template.env = env.clone();
+    }
+
+    template::validate(&app.command_template).map_err(UpdateError::InvalidData)?;
+
+    if let Some(wayland_flags) = options.wayland_flags.as_ref() {
+        app.session_flags.wayland =
+            session::parse_flags(wayland_flags).map_err(UpdateError::InvalidData)?;
+    }
+
+    if let Some(x11_flags) = options.x11_flags.as_ref() {
+        app.session_flags.x11 =
+            session::parse_flags(x11_flags).map_err(UpdateError::InvalidData)?;
+    }
+
+    // the window class and with it the entries depend on the engine, they are rewritten below
+    if let Some(engine) = options.engine.as_ref() {
+        app.engine = match engine.as_str() {
+            "" => None,
+            engine => Some(engine.parse::<Engine>().map_err(UpdateError::InvalidData)?),
+        };
+    }
+
+    let previous_name = AppName::parse(&previous.name).ok();
+    let shim_changed = app.shim != previous.shim
+        || previous_name.as_ref().map(shim_data::shim_name)
+            != Some(shim_data::shim_name(&app_name));
+    if app.shim && shim_changed {
+        shim_data::check_shim(&app_name, config).map_err(|e| match e {
+            shim_data::ShimDataError::CommandExists(path) => UpdateError::InvalidData(format!(
+                "The {} launcher would shadow the existing command {}",
+                shim_data::shim_name(&app_name),
+                path.display()
+            )),
+            e => UpdateError::Io(std::io::Error::other(format!("{:?}", e))),
+        })?;
+    }
+
     app_data::update_app(&name, &app, config).map_err(|e| {
         UpdateError::Io(std::io::Error::new(
             std::io::ErrorKind::Other,
@@ -52,5 +313,454 @@ pub fn update(
         ))
     })?;
 
+    desktop_data::sync_menu(config)
+        .map_err(|e| UpdateError::Io(std::io::Error::other(format!("{:?}", e))))?;
+
+    if let Some(previous_name) = &previous_name
+        && *previous_name != app_name
+    {
+        // entries are named after the app, a stale one would be recovered as a second app
+        if previous_name.slug() != app_name.slug() {
+            match desktop_data::remove_entry(previous_name, config) {
+                Err(desktop_data::DesktopDataError::Io(e))
+                    if e.kind() == std::io::ErrorKind::NotFound => {}
+                result => result
+                    .map_err(|e| UpdateError::Io(std::io::Error::other(format!("{:?}", e))))?,
+            }
+        }
+        desktop_data::remove_autostart_entry(previous_name, config)
+            .map_err(|e| UpdateError::Io(std::io::Error::other(format!("{:?}", e))))?;
+    }
+    desktop_data::update_autostart_entry(&app, config)
+        .map_err(|e| UpdateError::Io(std::io::Error::other(format!("{:?}", e))))?;
+
+    if shim_changed && let Some(previous_name) = &previous_name {
+        shim_data::remove_shim(previous_name, config)
+            .map_err(|e| UpdateError::Io(std::io::Error::other(format!("{:?}", e))))?;
+    }
+    if app.shim {
+        shim_data::create_shim(&app, config)
+            .map_err(|e| UpdateError::Io(std::io::Error::other(format!("{:?}", e))))?;
+    }
+
+    compositor::sync(config)
+        .map_err(|e| UpdateError::Io(std::io::Error::other(format!("{:?}", e))))?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::install;
+
+    #[test]
+    fn test_update_moves_shim() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        let options = install::InstallOptions {
+            shim: true,
+            ..Default::default()
+        };
+        install::install("Test App", "https://example.com", &options, &config).unwrap();
+
+        let options = UpdateOptions {
+            name: Some("Other App".to_string()),
+            url: None,
+            browser_path: None,
+            isolated: None,
+            shim: None,
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        assert!(!config.bin_path.join("test-app").exists());
+        assert!(config.bin_path.join("other-app").exists());
+        assert!(!config.desktop_data_path.join("test_app.desktop").exists());
+        assert!(config.desktop_data_path.join("other_app.desktop").exists());
+
+        let options = UpdateOptions {
+            name: None,
+            url: None,
+            browser_path: None,
+            isolated: None,
+            shim: Some(false),
+            ..Default::default()
+        };
+        update("Other App", &options, &config).unwrap();
+        assert!(!config.bin_path.join("other-app").exists());
+    }
+
+    #[test]
+    fn test_update_rejects_unsafe_names() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        install::install(
+            "Test App",
+            "https://example.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+
+        for name in ["Test\nExec=reboot", "../evil", "a/b"] {
+            let options = UpdateOptions {
+                name: Some(name.to_string()),
+                ..Default::default()
+            };
+            assert!(matches!(
+                update("Test App", &options, &config),
+                Err(UpdateError::InvalidData(_))
+            ));
+        }
+        assert!(app_data::get_app("Test App", &config).unwrap().is_some());
+
+        // values written into compositor configs
+        for options in [
+            UpdateOptions {
+                workspace: Some("1\nexec = sh -c reboot".to_string()),
+                ..Default::default()
+            },
+            UpdateOptions {
+                hotkey: Some("SUPER+G\nexec = reboot".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                update("Test App", &options, &config),
+                Err(UpdateError::InvalidData(_))
+            ));
+        }
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert!(app.window.workspace.is_none());
+        assert!(app.hotkey.is_none());
+    }
+
+    #[test]
+    fn test_update_engine() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        let options = install::InstallOptions {
+            tags: vec!["Mail".to_string()],
+            ..Default::default()
+        };
+        install::install("Test App", "https://example.com", &options, &config).unwrap();
+        let entry_path = config.desktop_data_path.join("test_app.desktop");
+
+        let options = UpdateOptions {
+            engine: Some("epiphany".to_string()),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert_eq!(app.engine, Some(Engine::Epiphany));
+        assert_eq!(app.tags, vec!["Mail"]);
+        assert!(
+            std::fs::read_to_string(&entry_path)
+                .unwrap()
+                .contains("StartupWMClass=org.gnome.Epiphany.WebApp_tarantula_test_app\n")
+        );
+
+        // an empty engine goes back to the browser's
+        let options = UpdateOptions {
+            engine: Some(String::new()),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        assert_eq!(
+            app_data::get_app("Test App", &config)
+                .unwrap()
+                .unwrap()
+                .engine,
+            None
+        );
+        assert!(
+            std::fs::read_to_string(&entry_path)
+                .unwrap()
+                .contains("StartupWMClass=tarantula-test_app\n")
+        );
+
+        let options = UpdateOptions {
+            engine: Some("webkit".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            update("Test App", &options, &config),
+            Err(UpdateError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_update_launch_options() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        install::install(
+            "Test App",
+            "https://example.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+
+        let options = UpdateOptions {
+            geometry: Some("1200x800+0+-20".to_string()),
+            scale: Some(1.25),
+            language: Some("de-DE".to_string()),
+            user_agent: Some("Mobile".to_string()),
+            tab: Some(true),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let launch = app_data::get_app("Test App", &config)
+            .unwrap()
+            .unwrap()
+            .launch;
+        assert_eq!(launch.geometry.unwrap().to_string(), "1200x800+0+-20");
+        assert_eq!(launch.scale, Some(1.25));
+        assert_eq!(launch.language.as_deref(), Some("de-DE"));
+        assert_eq!(launch.user_agent.as_deref(), Some("Mobile"));
+        assert!(launch.tab);
+
+        let options = UpdateOptions {
+            geometry: Some(String::new()),
+            scale: Some(1.0),
+            language: Some(String::new()),
+            user_agent: Some(String::new()),
+            tab: Some(false),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert!(app.launch.is_empty());
+
+        for options in [
+            UpdateOptions {
+                geometry: Some("1200".to_string()),
+                ..Default::default()
+            },
+            UpdateOptions {
+                scale: Some(0.0),
+                ..Default::default()
+            },
+            UpdateOptions {
+                language: Some("de DE".to_string()),
+                ..Default::default()
+            },
+            UpdateOptions {
+                user_agent: Some("Mobile\nX".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                update("Test App", &options, &config),
+                Err(UpdateError::InvalidData(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_update_network() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        install::install(
+            "Test App",
+            "https://example.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+
+        // network settings isolate the app
+        let options = UpdateOptions {
+            proxy: Some("socks5://localhost:1080".to_string()),
+            proxy_bypass: Some(vec!["<local>".to_string()]),
+            host_rules: Some("MAP *.corp 10.0.0.53".to_string()),
+            secure_dns: Some("secure".to_string()),
+            doh_template: Some("https://dns.example.com/dns-query".to_string()),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert!(app.isolated);
+        assert_eq!(
+            app.network.proxy.as_deref(),
+            Some("socks5://localhost:1080")
+        );
+        assert_eq!(app.network.proxy_bypass, vec!["<local>".to_string()]);
+        assert_eq!(app.network.secure_dns, Some(SecureDns::Secure));
+
+        let options = UpdateOptions {
+            isolated: Some(false),
+            ..Default::default()
+        };
+        assert!(matches!(
+            update("Test App", &options, &config),
+            Err(UpdateError::InvalidData(_))
+        ));
+
+        for options in [
+            UpdateOptions {
+                proxy: Some("ftp://localhost:21".to_string()),
+                ..Default::default()
+            },
+            UpdateOptions {
+                proxy: Some(String::new()),
+                ..Default::default()
+            },
+            UpdateOptions {
+                host_rules: Some("*.corp 10.0.0.53".to_string()),
+                ..Default::default()
+            },
+            UpdateOptions {
+                doh_template: Some(String::new()),
+                ..Default::default()
+            },
+            UpdateOptions {
+                secure_dns: Some("strict".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                update("Test App", &options, &config),
+                Err(UpdateError::InvalidData(_))
+            ));
+        }
+
+        let options = UpdateOptions {
+            proxy: Some(String::new()),
+            proxy_bypass: Some(Vec::new()),
+            host_rules: Some(String::new()),
+            secure_dns: Some(String::new()),
+            doh_template: Some(String::new()),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert!(app.network.is_empty());
+        assert!(app.isolated);
+    }
+
+    #[test]
+    fn test_update_command_template() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        install::install(
+            "Test App",
+            "https://example.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+
+        let options = UpdateOptions {
+            launch_template: Some("systemd-run --user --scope {browser} {engine_args}".to_string()),
+            wrapper: Some("prime-run".to_string()),
+            env: Some(BTreeMap::from([(
+                "MOZ_ENABLE_WAYLAND".to_string(),
+                "1".to_string(),
+            )])),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert_eq!(app.command_template.wrapper.as_deref(), Some("prime-run"));
+        assert_eq!(app.command_template.env["MOZ_ENABLE_WAYLAND"], "1");
+
+        let options = UpdateOptions {
+            launch_template: Some("{wrapper} {engine_args}".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            update("Test App", &options, &config),
+            Err(UpdateError::InvalidData(_))
+        ));
+
+        let options = UpdateOptions {
+            launch_template: Some(String::new()),
+            wrapper: Some(String::new()),
+            env: Some(BTreeMap::new()),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert!(app.command_template.is_empty());
+    }
+
+    #[test]
+    fn test_update_session_flags() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        install::install(
+            "Test App",
+            "https://example.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+
+        let options = UpdateOptions {
+            wayland_flags: Some("--ozone-platform=wayland --disable-gpu-compositing".to_string()),
+            x11_flags: Some(String::new()),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert_eq!(
+            app.session_flags.wayland,
+            Some(vec![
+                "--ozone-platform=wayland".to_string(),
+                "--disable-gpu-compositing".to_string()
+            ])
+        );
+        assert_eq!(app.session_flags.x11, Some(Vec::new()));
+
+        let options = UpdateOptions {
+            wayland_flags: Some("default".to_string()),
+            x11_flags: Some("default".to_string()),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert!(app.session_flags.is_empty());
+    }
+
+    #[test]
+    fn test_update_sandbox() {
+        let (config, _dir, _desktop_dir) = crate::test::create_test_config::create_test_config();
+        install::install(
+            "Test App",
+            "https://example.com",
+            &install::InstallOptions::default(),
+            &config,
+        )
+        .unwrap();
+
+        // a sandbox isolates the app
+        let options = UpdateOptions {
+            sandbox: Some("bwrap".to_string()),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert_eq!(app.sandbox, Some(SandboxMode::Bubblewrap));
+        assert!(app.isolated);
+
+        for options in [
+            UpdateOptions {
+                isolated: Some(false),
+                ..Default::default()
+            },
+            UpdateOptions {
+                sandbox: Some("docker".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                update("Test App", &options, &config),
+                Err(UpdateError::InvalidData(_))
+            ));
+        }
+
+        let options = UpdateOptions {
+            sandbox: Some(String::new()),
+            isolated: Some(false),
+            ..Default::default()
+        };
+        update("Test App", &options, &config).unwrap();
+        let app = app_data::get_app("Test App", &config).unwrap().unwrap();
+        assert_eq!(app.sandbox, None);
+        assert!(!app.isolated);
+    }
+}
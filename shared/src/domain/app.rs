@@ -1,8 +1,573 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+use crate::domain::app_name::AppName;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct App {
     pub name: String,
     pub url: String,
     pub icon: Option<String>,
+    // browser executable to use instead of the configured one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser_path: Option<String>,
+    // run the app in its own browser profile
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub isolated: bool,
+    // existing profile directory to use for an isolated app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_path: Option<String>,
+    // keep a launcher shim for the app in the bin directory
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub shim: bool,
+    // placement hints for tiling compositors
+    #[serde(default, skip_serializing_if = "WindowRules::is_empty")]
+    pub window: WindowRules,
+    // key combination that launches the app, e.g. SUPER+SHIFT+G
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotkey: Option<String>,
+    // submenus of the "Web Apps" menu the app is listed in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    // desktop entry metadata used by launcher search
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    // display names keyed by locale, e.g. "de" or "pt_BR"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub localized_names: BTreeMap<String, String>,
+    // keep the app out of launcher menus
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub hidden: bool,
+    // quick links shown when right-clicking the app in a launcher
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<AppAction>,
+    // launch the app when the user logs in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autostart: Option<Autostart>,
+    // browser engine to run the app in, by default the one of the browser
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine: Option<Engine>,
+    // how the app's window is opened, translated by each engine
+    #[serde(default, skip_serializing_if = "LaunchOptions::is_empty")]
+    pub launch: LaunchOptions,
+    // proxy and DNS settings, which need an isolated profile
+    #[serde(default, skip_serializing_if = "NetworkOptions::is_empty")]
+    pub network: NetworkOptions,
+    // how the browser command is wrapped, over the one in the config
+    #[serde(default, skip_serializing_if = "CommandTemplate::is_empty")]
+    pub command_template: CommandTemplate,
+    // Chromium flags for Wayland and X11 sessions, over the config's and the defaults
+    #[serde(default, skip_serializing_if = "SessionFlags::is_empty")]
+    pub session_flags: SessionFlags,
+    // run the browser in bubblewrap or firejail, which needs an isolated profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxMode>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LaunchOptions {
+    // initial window size and position
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<Geometry>,
+    // device scale factor, e.g. 1.25
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f32>,
+    // language the app's pages are requested in, e.g. de-DE
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    // open the app in a normal browser tab instead of an app window
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub tab: bool,
+}
+
+impl LaunchOptions {
+    pub fn is_empty(&self) -> bool {
+        *self == LaunchOptions::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(scale) = self.scale
+            && !(scale > 0.0 && scale <= 5.0)
+        {
+            return Err(format!(
+                "Invalid scale {}, expected a number above 0 and up to 5",
+                scale
+            ));
+        }
+        if let Some(language) = &self.language
+            && (language.is_empty()
+                || !language
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        {
+            return Err(format!(
+                "Invalid language {}, expected a language tag like de-DE",
+                language
+            ));
+        }
+        if let Some(user_agent) = &self.user_agent
+            && user_agent.chars().any(char::is_control)
+        {
+            return Err("The user agent cannot contain control characters".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Window size with an optional position, written as WIDTHxHEIGHT or WIDTHxHEIGHT+X+Y.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Geometry {
+    pub width: u32,
+    pub height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<(i32, i32)>,
+}
+
+impl std::str::FromStr for Geometry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Invalid geometry {}, expected WIDTHxHEIGHT or WIDTHxHEIGHT+X+Y",
+                s
+            )
+        };
+        let (size, position) = match s.split_once('+') {
+            Some((size, position)) => (size, Some(position)),
+            None => (s, None),
+        };
+        let size: WindowSize = size.parse().map_err(|_| invalid())?;
+        let position = match position {
+            Some(position) => {
+                let (x, y) = position.split_once('+').ok_or_else(invalid)?;
+                Some((
+                    x.trim().parse().map_err(|_| invalid())?,
+                    y.trim().parse().map_err(|_| invalid())?,
+                ))
+            }
+            None => None,
+        };
+        Ok(Geometry {
+            width: size.width,
+            height: size.height,
+            position,
+        })
+    }
+}
+
+impl std::fmt::Display for Geometry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)?;
+        if let Some((x, y)) = self.position {
+            write!(f, "+{}+{}", x, y)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct NetworkOptions {
+    // proxy server as SCHEME://HOST:PORT, e.g. socks5://localhost:1080
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    // hosts that don't go through the proxy, e.g. *.example.com or <local>
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proxy_bypass: Vec<String>,
+    // comma-separated MAP and EXCLUDE rules, e.g. MAP *.corp 10.0.0.53
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_rules: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secure_dns: Option<SecureDns>,
+    // DNS-over-HTTPS server template, e.g. https://dns.example.com/dns-query{?dns}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doh_template: Option<String>,
+}
+
+impl NetworkOptions {
+    pub fn is_empty(&self) -> bool {
+        *self == NetworkOptions::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(proxy) = &self.proxy {
+            proxy_address(proxy)?;
+        }
+        if let Some(host) = self
+            .proxy_bypass
+            .iter()
+            .find(|h| h.is_empty() || h.contains(|c: char| c == ';' || c.is_whitespace()))
+        {
+            return Err(format!(
+                "Invalid proxy bypass entry \"{}\", expected a host like *.example.com",
+                host
+            ));
+        }
+        if !self.proxy_bypass.is_empty() && self.proxy.is_none() {
+            return Err("A proxy bypass list needs a proxy".to_string());
+        }
+        if let Some(rules) = &self.host_rules
+            && let Some(rule) = rules.split(',').map(str::trim).find(|rule| {
+                rule.chars().any(char::is_control)
+                    || !(rule.starts_with("MAP ") || rule.starts_with("EXCLUDE "))
+            })
+        {
+            return Err(format!(
+                "Invalid host resolver rule \"{}\", expected MAP <host> <address> or EXCLUDE <host>",
+                rule
+            ));
+        }
+        if let Some(template) = &self.doh_template
+            && !url::Url::parse(template).is_ok_and(|url| url.scheme() == "https")
+        {
+            return Err(format!(
+                "Invalid DNS-over-HTTPS template {}, expected an https URL",
+                template
+            ));
+        }
+        if self.secure_dns == Some(SecureDns::Secure) && self.doh_template.is_none() {
+            return Err("Secure DNS needs a DNS-over-HTTPS template".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Host and port of a proxy written as [SCHEME://]HOST:PORT, which is the form
+// Chromium's --proxy-server takes for a single proxy.
+pub fn proxy_address(proxy: &str) -> Result<(String, u16), String> {
+    let invalid = || {
+        format!(
+            "Invalid proxy {}, expected SCHEME://HOST:PORT with http, https, socks4 or socks5",
+            proxy
+        )
+    };
+    let url = if proxy.contains("://") {
+        url::Url::parse(proxy)
+    } else {
+        url::Url::parse(&format!("http://{}", proxy))
+    }
+    .map_err(|_| invalid())?;
+    if !["http", "https", "socks4", "socks5"].contains(&url.scheme())
+        || !url.username().is_empty()
+        || !matches!(url.path(), "" | "/")
+        || url.query().is_some()
+    {
+        return Err(invalid());
+    }
+    let host = url
+        .host_str()
+        .filter(|h| !h.is_empty())
+        .ok_or_else(invalid)?;
+    let port = url.port_or_known_default().ok_or_else(invalid)?;
+    Ok((host.trim_matches(['[', ']']).to_string(), port))
+}
+
+// Chromium's DNS-over-HTTPS modes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SecureDns {
+    Off,
+    // use DNS-over-HTTPS when the server supports it, fall back to plain DNS
+    Automatic,
+    // only DNS-over-HTTPS through the template
+    Secure,
+}
+
+impl std::str::FromStr for SecureDns {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(SecureDns::Off),
+            "automatic" => Ok(SecureDns::Automatic),
+            "secure" => Ok(SecureDns::Secure),
+            _ => Err(format!(
+                "Unknown secure DNS mode {}, expected off, automatic or secure",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for SecureDns {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SecureDns::Off => "off",
+            SecureDns::Automatic => "automatic",
+            SecureDns::Secure => "secure",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Command line an app is launched with, see run::template for the placeholders.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CommandTemplate {
+    // e.g. "systemd-run --user --scope {browser} {engine_args}", run::template::DEFAULT if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    // command the browser runs in, e.g. prime-run or "env MOZ_ENABLE_WAYLAND=1"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrapper: Option<String>,
+    // arguments passed to the browser after the engine's own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_args: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl CommandTemplate {
+    pub fn is_empty(&self) -> bool {
+        *self == CommandTemplate::default()
+    }
+
+    // The app's settings over the config's. Variables are merged, the app's win.
+    pub fn or(&self, fallback: &CommandTemplate) -> CommandTemplate {
+        let mut env = fallback.env.clone();
+        env.extend(self.env.clone());
+        CommandTemplate {
+            template: self.template.clone().or(fallback.template.clone()),
+            wrapper: self.wrapper.clone().or(fallback.wrapper.clone()),
+            extra_args: self.extra_args.clone().or(fallback.extra_args.clone()),
+            env,
+        }
+    }
+}
+
+// Flags passed to Chromium depending on the display server, None keeps the
+// defaults and an empty list passes none.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SessionFlags {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wayland: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x11: Option<Vec<String>>,
+}
+
+impl SessionFlags {
+    pub fn is_empty(&self) -> bool {
+        *self == SessionFlags::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxMode {
+    // bubblewrap if it's installed, otherwise firejail
+    Auto,
+    Bubblewrap,
+    Firejail,
+}
+
+impl std::str::FromStr for SandboxMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(SandboxMode::Auto),
+            "bubblewrap" | "bwrap" => Ok(SandboxMode::Bubblewrap),
+            "firejail" => Ok(SandboxMode::Firejail),
+            _ => Err(format!(
+                "Unknown sandbox {}, expected auto, bubblewrap or firejail",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for SandboxMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SandboxMode::Auto => "auto",
+            SandboxMode::Bubblewrap => "bubblewrap",
+            SandboxMode::Firejail => "firejail",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Engine {
+    Chromium,
+    Firefox,
+    Epiphany,
+    // tarantula's own webview window
+    Embedded,
+}
+
+impl std::str::FromStr for Engine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "chromium" => Ok(Engine::Chromium),
+            "firefox" => Ok(Engine::Firefox),
+            "epiphany" => Ok(Engine::Epiphany),
+            "embedded" => Ok(Engine::Embedded),
+            _ => Err(format!(
+                "Unknown engine {}, expected chromium, firefox, epiphany or embedded",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Engine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Engine::Chromium => "chromium",
+            Engine::Firefox => "firefox",
+            Engine::Epiphany => "epiphany",
+            Engine::Embedded => "embedded",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AppAction {
+    pub label: String,
+    // a URL, or a path resolved against the app's URL like a deep link
+    pub target: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Autostart {
+    // seconds to wait after login before launching
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u32>,
+    // ask the browser to start the window minimized
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub minimized: bool,
+}
+
+impl App {
+    // Window class (X11) and app id (Wayland) the app's windows are launched with,
+    // so docks and compositors can tell apps apart.
+    pub fn window_class(&self) -> String {
+        match self.engine {
+            // Epiphany names the windows after the app id, which can't be set
+            Some(Engine::Epiphany) => self.epiphany_id(),
+            _ => match AppName::parse(&self.name) {
+                Ok(name) => format!("tarantula-{}", name.slug()),
+                // URLs opened without installing an app share a class
+                Err(_) => "tarantula".to_string(),
+            },
+        }
+    }
+
+    // App id Epiphany derives from the name of the profile directory. Only
+    // letters, digits and underscores are valid in its last element, so other
+    // characters of the slug are written as their code point between two Xs, which
+    // the lowercase slug can't contain otherwise: mail-work becomes mailX2DXwork.
+    pub fn epiphany_id(&self) -> String {
+        let slug = match AppName::parse(&self.name) {
+            Ok(name) => name.slug(),
+            Err(_) => self.name.clone(),
+        };
+        let id: String = slug
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | '0'..='9' | '_' => c.to_string(),
+                _ => format!("X{:X}X", u32::from(c)),
+            })
+            .collect();
+        format!("org.gnome.Epiphany.WebApp_tarantula_{}", id)
+    }
+
+    // The browser ignores the switches when the profile is already open for
+    // another app, and a sandbox only exposes the app's own profile, so these
+    // settings are only valid in an isolated profile.
+    pub fn validate_isolation(&self) -> Result<(), String> {
+        self.network.validate()?;
+        let setting = if !self.network.is_empty() {
+            "network settings"
+        } else if self.sandbox.is_some() {
+            "sandbox"
+        } else {
+            return Ok(());
+        };
+        if !self.isolated {
+            return Err(format!(
+                "The {} of {} need an isolated profile",
+                setting, self.name
+            ));
+        }
+        Ok(())
+    }
+
+    // Settings that isolate the app when they are added.
+    pub fn needs_isolation(&self) -> bool {
+        !self.network.is_empty() || self.sandbox.is_some()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct WindowRules {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub float: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<WindowSize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f32>,
+}
+
+impl WindowRules {
+    pub fn is_empty(&self) -> bool {
+        *self == WindowRules::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(workspace) = &self.workspace
+            && !is_valid_workspace(workspace)
+        {
+            return Err(format!(
+                "Invalid workspace {:?}, expected a number or a name like name:mail",
+                workspace
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Workspaces are written into compositor configs as they are, so they're limited to
+// characters that can't end the line or start another option.
+pub fn is_valid_workspace(workspace: &str) -> bool {
+    !workspace.is_empty()
+        && workspace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | ':' | '-'))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl std::str::FromStr for WindowSize {
+    type Err = String;
+
+    // parses sizes written as WIDTHxHEIGHT, e.g. 1200x800
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid size {}, expected WIDTHxHEIGHT", s);
+        let (width, height) = s.split_once('x').ok_or_else(invalid)?;
+        Ok(WindowSize {
+            width: width.trim().parse().map_err(|_| invalid())?,
+            height: height.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
 }
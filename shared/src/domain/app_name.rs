@@ -0,0 +1,163 @@
+use std::fmt;
+
+const MAX_LENGTH: usize = 64;
+
+// Characters the Desktop Entry spec reserves in Exec arguments, plus the field code marker.
+// They would also need quoting in shims and compositor configs.
+const RESERVED_CHARS: &str = "\"'\\`$;&|<>*?#()~%";
+
+// An app name that is safe to use in file names, desktop entries, shims and
+// compositor configs. The slug is the lowercase name with spaces as underscores.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppName(String);
+
+#[derive(Debug, PartialEq)]
+pub enum AppNameError {
+    Empty,
+    TooLong,
+    InvalidCharacter(char),
+    // names starting with a dot, which would be hidden files or point at a parent directory
+    LeadingDot,
+    // names starting with a dash, which the launcher would parse as an option
+    LeadingDash,
+}
+
+impl fmt::Display for AppNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppNameError::Empty => write!(f, "App name cannot be empty"),
+            AppNameError::TooLong => {
+                write!(
+                    f,
+                    "App name cannot be longer than {} characters",
+                    MAX_LENGTH
+                )
+            }
+            AppNameError::InvalidCharacter(c) => {
+                write!(f, "App name cannot contain {:?}", c)
+            }
+            AppNameError::LeadingDot => write!(f, "App name cannot start with a dot"),
+            AppNameError::LeadingDash => write!(f, "App name cannot start with a dash"),
+        }
+    }
+}
+
+impl AppName {
+    pub fn parse(name: &str) -> Result<Self, AppNameError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppNameError::Empty);
+        }
+        if name.chars().count() > MAX_LENGTH {
+            return Err(AppNameError::TooLong);
+        }
+        if name.starts_with('.') {
+            return Err(AppNameError::LeadingDot);
+        }
+        if name.starts_with('-') {
+            return Err(AppNameError::LeadingDash);
+        }
+        if let Some(c) = name.chars().find(|c| !is_allowed(*c)) {
+            return Err(AppNameError::InvalidCharacter(c));
+        }
+
+        Ok(Self(name.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn slug(&self) -> String {
+        self.0.to_lowercase().replace(' ', "_")
+    }
+}
+
+impl fmt::Display for AppName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn is_allowed(c: char) -> bool {
+    if c == ' ' {
+        return true;
+    }
+    // newlines, tabs and other whitespace would split keys or arguments
+    if c.is_control() || c.is_whitespace() {
+        return false;
+    }
+    // zero-width and bidi control characters can disguise a name
+    if matches!(c, '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' | '\u{FEFF}')
+    {
+        return false;
+    }
+
+    c != '/' && !RESERVED_CHARS.contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_names() {
+        for name in [
+            "Gmail",
+            "Proton Mail",
+            "Ko-fi",
+            "Täglich",
+            "App_1.0",
+            "Google+",
+        ] {
+            assert_eq!(AppName::parse(name).unwrap().as_str(), name);
+        }
+        assert_eq!(
+            AppName::parse("  Proton Mail ").unwrap().slug(),
+            "proton_mail"
+        );
+    }
+
+    #[test]
+    fn test_attack_strings() {
+        let cases = [
+            ("", AppNameError::Empty),
+            ("   ", AppNameError::Empty),
+            ("Mail\nExec=rm -rf ~", AppNameError::InvalidCharacter('\n')),
+            ("Mail\rExec=sh", AppNameError::InvalidCharacter('\r')),
+            ("Mail\tTab", AppNameError::InvalidCharacter('\t')),
+            (
+                "Mail\u{2028}Exec=sh",
+                AppNameError::InvalidCharacter('\u{2028}'),
+            ),
+            ("../../.bashrc", AppNameError::LeadingDot),
+            ("..", AppNameError::LeadingDot),
+            (".hidden", AppNameError::LeadingDot),
+            ("--print-command", AppNameError::LeadingDash),
+            ("-h", AppNameError::LeadingDash),
+            (" -V", AppNameError::LeadingDash),
+            ("apps/../../evil", AppNameError::InvalidCharacter('/')),
+            ("C:\\evil", AppNameError::InvalidCharacter('\\')),
+            ("$(reboot)", AppNameError::InvalidCharacter('$')),
+            ("`id`", AppNameError::InvalidCharacter('`')),
+            ("it's", AppNameError::InvalidCharacter('\'')),
+            ("say \"hi\"", AppNameError::InvalidCharacter('"')),
+            ("mail; rm -rf ~", AppNameError::InvalidCharacter(';')),
+            ("mail && reboot", AppNameError::InvalidCharacter('&')),
+            ("mail | sh", AppNameError::InvalidCharacter('|')),
+            ("mail > /etc/passwd", AppNameError::InvalidCharacter('>')),
+            ("mail %u", AppNameError::InvalidCharacter('%')),
+            (
+                "mail\u{202E}fdp.exe",
+                AppNameError::InvalidCharacter('\u{202E}'),
+            ),
+            ("mail\0", AppNameError::InvalidCharacter('\0')),
+        ];
+        for (name, error) in cases {
+            assert_eq!(AppName::parse(name), Err(error), "{:?}", name);
+        }
+
+        assert_eq!(AppName::parse(&"a".repeat(65)), Err(AppNameError::TooLong));
+        assert!(AppName::parse(&"a".repeat(64)).is_ok());
+    }
+}
@@ -1,4 +1,7 @@
 pub mod app_data;
+pub mod browsers;
 pub mod config_data;
 pub mod desktop_data;
+pub mod desktop_entry;
 pub mod icons;
+pub mod shim_data;
@@ -1,8 +1,12 @@
 use std::fs::{self, File};
 use std::io::Write;
+use std::path::PathBuf;
 
 use crate::app::config;
 use crate::domain::app;
+use crate::domain::app::Engine;
+use crate::domain::app_name::AppName;
+use crate::infra::browsers::{BrowserCommand, Sandbox};
 
 #[derive(Debug)]
 pub enum ProjectDataError {
@@ -26,9 +30,15 @@ pub fn get_app(
 ) -> Result<Option<app::App>, ProjectDataError> {
     let apps = get_apps(config)?;
 
-    Ok(apps.into_iter().find(|a| {
-        a.name.to_lowercase().replace(" ", "_") == app_name.to_lowercase().replace(" ", "_")
-    }))
+    // names match by slug, so `tarantula proton_mail` finds "Proton Mail"; names that
+    // fail validation, e.g. saved by older versions, only match exactly
+    let slug = AppName::parse(app_name).ok().map(|name| name.slug());
+    Ok(apps
+        .into_iter()
+        .find(|a| match (&slug, AppName::parse(&a.name)) {
+            (Some(slug), Ok(name)) => name.slug() == *slug,
+            _ => a.name == app_name,
+        }))
 }
 
 pub fn get_apps(config: &config::Config) -> Result<Vec<app::App>, ProjectDataError> {
@@ -83,6 +93,20 @@ fn write_data_file(apps: &Vec<app::App>, config: &config::Config) -> Result<(),
     Ok(())
 }
 
+// Moves apps.json aside, e.g. when it can't be parsed, and returns where it went.
+pub fn backup_data_file(config: &config::Config) -> Result<PathBuf, std::io::Error> {
+    let path = config.app_data_path.join("apps.json");
+    let mut backup = path.with_extension("json.bak");
+    let mut n = 1;
+    while backup.exists() {
+        backup = path.with_extension(format!("json.bak.{}", n));
+        n += 1;
+    }
+
+    fs::rename(&path, &backup)?;
+    Ok(backup)
+}
+
 pub fn set_browser_path(path: &str, config: &config::Config) -> Result<(), ProjectDataError> {
     let mut new_config = config.clone();
     new_config.browser_path = Some(path.to_string());
@@ -98,6 +122,57 @@ pub fn get_browser_path(config: &config::Config) -> Option<String> {
     config.browser_path.clone()
 }
 
+// Profile directory of the app for the engine, None to use the browser's
+// default profile. Sandboxed browsers get profiles in a directory the sandbox can write to.
+pub fn get_profile_path(
+    app: &app::App,
+    engine: Engine,
+    browser: Option<&BrowserCommand>,
+    config: &config::Config,
+) -> Option<PathBuf> {
+    let slug = AppName::parse(&app.name).ok()?.slug();
+    match engine {
+        Engine::Chromium => {
+            if let Some(path) = &app.profile_path {
+                return Some(PathBuf::from(path));
+            }
+            if !app.isolated {
+                return None;
+            }
+            Some(profile_root(browser, config).join("profiles").join(slug))
+        }
+        // Firefox apps always run in a profile of their own, kept apart from
+        // Chromium profiles, which is what imported profile paths are
+        Engine::Firefox => Some(
+            profile_root(browser, config)
+                .join("firefox-profiles")
+                .join(slug),
+        ),
+        // Epiphany only runs apps from directories named after the app id,
+        // which it keeps in its data directory
+        Engine::Epiphany => {
+            let sandbox = browser.and_then(|b| b.sandbox.as_ref());
+            let data_dir = match (sandbox, dirs::home_dir()) {
+                (Some(Sandbox::Flatpak(id)), Some(home)) => {
+                    home.join(".var/app").join(id).join("data")
+                }
+                (Some(Sandbox::Snap(_)), _) => profile_root(browser, config),
+                _ => config.desktop_data_path.parent()?.to_path_buf(),
+            };
+            Some(data_dir.join(app.epiphany_id()))
+        }
+        // the webview keeps cookies and storage of each app apart
+        Engine::Embedded => Some(config.app_data_path.join("embedded-profiles").join(slug)),
+    }
+}
+
+fn profile_root(browser: Option<&BrowserCommand>, config: &config::Config) -> PathBuf {
+    dirs::home_dir()
+        .zip(browser)
+        .and_then(|(home, browser)| browser.profile_root(&home))
+        .unwrap_or(config.app_data_path.clone())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,6 +187,7 @@ mod tests {
             name: "Test App".to_string(),
             url: "https://example.com".to_string(),
             icon: None,
+            ..Default::default()
         };
         let result = add_app(app, &config);
         assert!(result.is_ok());
@@ -129,6 +205,7 @@ mod tests {
             name: "Test App".to_string(),
             url: "https://example.com".to_string(),
             icon: None,
+            ..Default::default()
         };
         add_app(app, &config).unwrap();
 
@@ -138,6 +215,10 @@ mod tests {
         let app = app.unwrap();
         assert_eq!(app.name, "Test App");
         assert_eq!(app.url, "https://example.com");
+
+        // launchers look apps up by slug
+        let app = get_app("test_app", &config).unwrap();
+        assert_eq!(app.unwrap().name, "Test App");
     }
 
     #[test]
@@ -155,6 +236,7 @@ mod tests {
             name: "Test App".to_string(),
             url: "https://example.com".to_string(),
             icon: None,
+            ..Default::default()
         };
         add_app(app.clone(), &config).unwrap();
 
@@ -171,6 +253,7 @@ mod tests {
             name: "Test App".to_string(),
             url: "https://example.com".to_string(),
             icon: None,
+            ..Default::default()
         };
 
         add_app(app, &config).unwrap();
@@ -179,6 +262,7 @@ mod tests {
             name: "Test App new".to_string(),
             url: "https://example2.com".to_string(),
             icon: None,
+            ..Default::default()
         };
         update_app("Test App", &new_app, &config).unwrap();
 
@@ -195,6 +279,7 @@ mod tests {
             name: "Test App".to_string(),
             url: "https://example.com".to_string(),
             icon: None,
+            ..Default::default()
         };
 
         add_app(app, &config).unwrap();
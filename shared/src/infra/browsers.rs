@@ -0,0 +1,744 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::app::config::Config;
+use crate::infra::desktop_entry::{self, DesktopFile, MAIN_GROUP};
+use crate::utils;
+
+// Finds installed browsers and the default one the way the XDG MIME Applications spec describes.
+// https://specifications.freedesktop.org/mime-apps-spec/latest/
+
+// MIME types of web links, in the order their default handler is looked up
+const WEB_MIME_TYPES: [&str; 3] = [
+    "x-scheme-handler/https",
+    "x-scheme-handler/http",
+    "text/html",
+];
+
+// Executables of Chromium-based browsers, which support --app and --user-data-dir
+const CHROMIUM_COMMANDS: [&str; 17] = [
+    "chromium",
+    "chromium-browser",
+    "ungoogled-chromium",
+    "google-chrome",
+    "google-chrome-stable",
+    "google-chrome-beta",
+    "google-chrome-unstable",
+    "brave",
+    "brave-browser",
+    "brave-browser-stable",
+    "microsoft-edge",
+    "microsoft-edge-stable",
+    "microsoft-edge-beta",
+    "vivaldi",
+    "vivaldi-stable",
+    "opera",
+    "thorium-browser",
+];
+
+const FIREFOX_COMMANDS: [&str; 7] = [
+    "firefox",
+    "firefox-esr",
+    "firefox-developer-edition",
+    "librewolf",
+    "waterfox",
+    "floorp",
+    "zen-browser",
+];
+
+// GNOME Web
+const EPIPHANY_COMMANDS: [&str; 2] = ["epiphany", "epiphany-browser"];
+
+// Flatpak app ids of Chromium-based browsers
+const CHROMIUM_FLATPAKS: [&str; 8] = [
+    "org.chromium.Chromium",
+    "io.github.ungoogled_software.ungoogled_chromium",
+    "com.google.Chrome",
+    "com.google.ChromeDev",
+    "com.brave.Browser",
+    "com.microsoft.Edge",
+    "com.vivaldi.Vivaldi",
+    "com.opera.Opera",
+];
+
+const FIREFOX_FLATPAKS: [&str; 5] = [
+    "org.mozilla.firefox",
+    "io.gitlab.librewolf-community",
+    "net.waterfox.waterfox",
+    "one.ablaze.floorp",
+    "app.zen_browser.zen",
+];
+
+const EPIPHANY_FLATPAKS: [&str; 2] = ["org.gnome.Epiphany", "org.gnome.Epiphany.Devel"];
+
+// Exec arguments that only make sense to the desktop, like field codes and
+// Flatpak's file forwarding markers
+const EXEC_PLACEHOLDERS: [&str; 11] = [
+    "%u",
+    "%U",
+    "%f",
+    "%F",
+    "%i",
+    "%c",
+    "%k",
+    "@@",
+    "@@u",
+    "@@f",
+    "--file-forwarding",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrowserFamily {
+    Chromium,
+    Firefox,
+    Epiphany,
+    Other,
+}
+
+// Sandboxes that only let the browser write to some directories.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sandbox {
+    // Flatpak app id, e.g. com.brave.Browser
+    Flatpak(String),
+    // snap name, e.g. chromium
+    Snap(String),
+}
+
+// How a browser is started, e.g. `flatpak run com.brave.Browser` for a Flatpak.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub sandbox: Option<Sandbox>,
+}
+
+impl BrowserCommand {
+    // Parses a configured browser, either a path or a command line.
+    pub fn parse(command: &str) -> Option<Self> {
+        // paths may contain spaces
+        if Path::new(command).is_file() {
+            return Self::from_args(vec![command.to_string()]);
+        }
+        Self::from_args(desktop_entry::parse_exec(command).ok()?)
+    }
+
+    // Builds the command from Exec arguments, dropping placeholders and `env VAR=value` prefixes.
+    fn from_args(args: Vec<String>) -> Option<Self> {
+        let mut args: Vec<String> = args
+            .into_iter()
+            .filter(|arg| !EXEC_PLACEHOLDERS.contains(&arg.as_str()))
+            .collect();
+        if args.first().is_some_and(|arg| file_name(arg) == "env") {
+            args.remove(0);
+            while args
+                .first()
+                .is_some_and(|arg| arg.contains('=') && !arg.starts_with('-'))
+            {
+                args.remove(0);
+            }
+        }
+        if args.is_empty() {
+            return None;
+        }
+
+        let program = args.remove(0);
+        let sandbox = sandbox(&program, &args);
+        Some(Self {
+            program,
+            args,
+            sandbox,
+        })
+    }
+
+    pub fn exists(&self) -> bool {
+        utils::find_in_path(&self.program).is_some()
+    }
+
+    pub fn family(&self) -> BrowserFamily {
+        match &self.sandbox {
+            Some(Sandbox::Flatpak(id)) if CHROMIUM_FLATPAKS.contains(&id.as_str()) => {
+                BrowserFamily::Chromium
+            }
+            Some(Sandbox::Flatpak(id)) if FIREFOX_FLATPAKS.contains(&id.as_str()) => {
+                BrowserFamily::Firefox
+            }
+            Some(Sandbox::Flatpak(id)) if EPIPHANY_FLATPAKS.contains(&id.as_str()) => {
+                BrowserFamily::Epiphany
+            }
+            Some(Sandbox::Flatpak(_)) => BrowserFamily::Other,
+            _ => family(&self.program),
+        }
+    }
+
+    // Where the browser can keep the profile of an app: inside the sandbox's own data
+    // directory, or None for browsers that can write anywhere.
+    pub fn profile_root(&self, home: &Path) -> Option<PathBuf> {
+        match &self.sandbox {
+            Some(Sandbox::Flatpak(id)) => {
+                Some(home.join(".var/app").join(id).join("data/tarantula"))
+            }
+            Some(Sandbox::Snap(name)) => {
+                Some(home.join("snap").join(name).join("common/tarantula"))
+            }
+            None => None,
+        }
+    }
+}
+
+impl std::fmt::Display for BrowserCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let words: Vec<String> = std::iter::once(&self.program)
+            .chain(self.args.iter())
+            .map(|word| utils::shell_quote(word))
+            .collect();
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Browser {
+    // desktop file id, e.g. google-chrome.desktop, empty for browsers only found on $PATH
+    pub id: String,
+    pub name: String,
+    // command line the browser's desktop entry runs, without the file arguments
+    pub command: BrowserCommand,
+    pub family: BrowserFamily,
+    // the browser handles web links by default
+    pub default: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct XdgDirs {
+    // $XDG_CONFIG_HOME followed by $XDG_CONFIG_DIRS
+    pub config_dirs: Vec<PathBuf>,
+    // $XDG_DATA_HOME followed by $XDG_DATA_DIRS
+    pub data_dirs: Vec<PathBuf>,
+    // lowercase $XDG_CURRENT_DESKTOP names, e.g. gnome
+    pub desktops: Vec<String>,
+}
+
+impl XdgDirs {
+    pub fn from_env(config: &Config) -> Self {
+        let env_dirs = |name: &str, default: &str| -> Vec<PathBuf> {
+            let value = std::env::var(name)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .unwrap_or(default.to_string());
+            std::env::split_paths(&value).collect()
+        };
+
+        let mut config_dirs = vec![config.xdg_config_path.clone()];
+        config_dirs.extend(env_dirs("XDG_CONFIG_DIRS", "/etc/xdg"));
+
+        // the applications directory sits in $XDG_DATA_HOME
+        let mut data_dirs: Vec<PathBuf> = config
+            .desktop_data_path
+            .parent()
+            .map(Path::to_path_buf)
+            .into_iter()
+            .collect();
+        data_dirs.extend(env_dirs("XDG_DATA_DIRS", "/usr/local/share:/usr/share"));
+
+        let desktops = std::env::var("XDG_CURRENT_DESKTOP")
+            .unwrap_or_default()
+            .split(':')
+            .filter(|d| !d.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+
+        Self {
+            config_dirs,
+            data_dirs,
+            desktops,
+        }
+    }
+
+    // mimeapps.list files, most important first
+    fn mimeapps_lists(&self) -> Vec<PathBuf> {
+        let dirs = self
+            .config_dirs
+            .iter()
+            .cloned()
+            .chain(self.data_dirs.iter().map(|d| d.join("applications")));
+
+        let mut lists = Vec::new();
+        for dir in dirs {
+            for desktop in &self.desktops {
+                lists.push(dir.join(format!("{}-mimeapps.list", desktop)));
+            }
+            lists.push(dir.join("mimeapps.list"));
+        }
+        lists
+    }
+}
+
+// Installed browsers, with the default one first.
+pub fn detect(dirs: &XdgDirs) -> Vec<Browser> {
+    let default_id = default_browser_id(dirs);
+
+    let mut browsers: Vec<Browser> = desktop_files(dirs)
+        .into_iter()
+        .filter_map(|(id, path)| {
+            let browser = read_browser(&id, &path)?;
+            Some(Browser {
+                default: default_id.as_deref() == Some(id.as_str()),
+                ..browser
+            })
+        })
+        .collect();
+    browsers.sort_by(|a, b| b.default.cmp(&a.default).then(a.name.cmp(&b.name)));
+    browsers
+}
+
+// The browser to use when none is configured or the configured one is missing:
+// the default browser if it is Chromium- or Firefox-based, then any installed
+// Chromium-based browser, then any Firefox-based one.
+pub fn fallback(dirs: &XdgDirs) -> Option<Browser> {
+    let default = detect(dirs).into_iter().find(|b| {
+        b.default && matches!(b.family, BrowserFamily::Chromium | BrowserFamily::Firefox)
+    });
+    default
+        .or_else(|| find(dirs, BrowserFamily::Chromium))
+        .or_else(|| find(dirs, BrowserFamily::Firefox))
+}
+
+// An installed browser of the family, the default browser first.
+pub fn find(dirs: &XdgDirs, family: BrowserFamily) -> Option<Browser> {
+    if let Some(browser) = detect(dirs).into_iter().find(|b| b.family == family) {
+        return Some(browser);
+    }
+
+    // browsers installed without a desktop entry
+    let commands: &[&str] = match family {
+        BrowserFamily::Chromium => &CHROMIUM_COMMANDS,
+        BrowserFamily::Firefox => &FIREFOX_COMMANDS,
+        BrowserFamily::Epiphany => &EPIPHANY_COMMANDS,
+        BrowserFamily::Other => &[],
+    };
+    commands.iter().find_map(|command| {
+        let path = utils::find_in_path(command)?;
+        Some(Browser {
+            id: String::new(),
+            name: command.to_string(),
+            command: BrowserCommand {
+                program: path.display().to_string(),
+                args: Vec::new(),
+                sandbox: None,
+            },
+            family,
+            default: false,
+        })
+    })
+}
+
+pub fn family(command: &str) -> BrowserFamily {
+    let name = file_name(command);
+    if CHROMIUM_COMMANDS.contains(&name) {
+        BrowserFamily::Chromium
+    } else if FIREFOX_COMMANDS.contains(&name) {
+        BrowserFamily::Firefox
+    } else if EPIPHANY_COMMANDS.contains(&name) {
+        BrowserFamily::Epiphany
+    } else {
+        BrowserFamily::Other
+    }
+}
+
+// Asks the browser for its version. Unknown browsers aren't asked, as they might open a window instead.
+pub fn version(browser: &Browser) -> Option<String> {
+    if browser.family == BrowserFamily::Other {
+        return None;
+    }
+
+    let mut child = Command::new(&browser.command.program)
+        .args(&browser.command.args)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let deadline = Instant::now() + Duration::from_secs(3);
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    let mut output = String::new();
+    child.stdout.take()?.read_to_string(&mut output).ok()?;
+    parse_version(&output)
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or(path)
+}
+
+// The sandbox a command runs the browser in: `flatpak run <id>`, the wrappers Flatpak
+// exports to .../flatpak/exports/bin/<id>, or the wrappers snapd puts in /snap/bin.
+fn sandbox(program: &str, args: &[String]) -> Option<Sandbox> {
+    if file_name(program) == "flatpak" && args.first().map(String::as_str) == Some("run") {
+        let id = args.iter().skip(1).find(|arg| !arg.starts_with('-'))?;
+        return Some(Sandbox::Flatpak(id.clone()));
+    }
+    if program.contains("/flatpak/exports/bin/") {
+        return Some(Sandbox::Flatpak(file_name(program).to_string()));
+    }
+    if program.starts_with("/snap/bin/") {
+        // commands of a snap's other apps are named <snap>.<app>
+        let name = file_name(program).split('.').next()?;
+        return Some(Sandbox::Snap(name.to_string()));
+    }
+    None
+}
+
+// Picks the version out of output like "Google Chrome 120.0.6099.109" or "Mozilla Firefox 121.0".
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()) && word.contains('.'))
+        .map(|word| word.trim_end_matches(',').to_string())
+}
+
+// The desktop file id of the default handler of web links.
+fn default_browser_id(dirs: &XdgDirs) -> Option<String> {
+    let installed: Vec<String> = desktop_files(dirs).into_iter().map(|(id, _)| id).collect();
+    let lists: Vec<DesktopFile> = dirs
+        .mimeapps_lists()
+        .iter()
+        .filter_map(|path| DesktopFile::read(path).ok())
+        .collect();
+
+    for mime_type in WEB_MIME_TYPES {
+        for list in &lists {
+            let defaults = match list.group("Default Applications") {
+                Some(group) => group.get_list(mime_type),
+                None => continue,
+            };
+            if let Some(id) = defaults.into_iter().find(|id| installed.contains(id)) {
+                return Some(id);
+            }
+        }
+    }
+
+    None
+}
+
+// Desktop file ids and paths of all applications. An id found in several
+// data directories belongs to the first one. Files in subdirectories get ids
+// like vendor-app.desktop.
+fn desktop_files(dirs: &XdgDirs) -> Vec<(String, PathBuf)> {
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
+    for dir in &dirs.data_dirs {
+        let applications = dir.join("applications");
+        let mut found = Vec::new();
+        collect_desktop_files(&applications, &applications, &mut found);
+        found.sort();
+        for (id, path) in found {
+            if !files.iter().any(|(existing, _)| *existing == id) {
+                files.push((id, path));
+            }
+        }
+    }
+    files
+}
+
+fn collect_desktop_files(root: &Path, dir: &Path, found: &mut Vec<(String, PathBuf)>) {
+    let read_dir = match std::fs::read_dir(dir) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    for dir_entry in read_dir.flatten() {
+        let path = dir_entry.path();
+        if path.is_dir() {
+            collect_desktop_files(root, &path, found);
+        } else if path.extension().and_then(|e| e.to_str()) == Some("desktop")
+            && let Ok(relative) = path.strip_prefix(root)
+        {
+            let id = relative.to_string_lossy().replace('/', "-");
+            found.push((id, path));
+        }
+    }
+}
+
+// Reads a desktop entry, returning a browser if it is an application that opens web links.
+fn read_browser(id: &str, path: &Path) -> Option<Browser> {
+    let file = DesktopFile::read(path).ok()?;
+    let entry = file.group(MAIN_GROUP)?;
+
+    // Hidden means the entry was deleted, NoDisplay entries can still handle links
+    if entry.get("Type").as_deref() != Some("Application") || entry.get_bool("Hidden") == Some(true)
+    {
+        return None;
+    }
+    let mime_types = entry.get_list("MimeType");
+    if !mime_types
+        .iter()
+        .any(|m| m == "x-scheme-handler/https" || m == "x-scheme-handler/http")
+    {
+        return None;
+    }
+
+    let command = BrowserCommand::from_args(desktop_entry::parse_exec(&entry.get("Exec")?).ok()?)?;
+
+    Some(Browser {
+        id: id.to_string(),
+        name: entry.get("Name").unwrap_or_else(|| id.to_string()),
+        family: command.family(),
+        command,
+        default: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME: &str = include_str!("../test/desktop_entries/google-chrome.desktop");
+    const FIREFOX: &str = include_str!("../test/desktop_entries/firefox.desktop");
+    const CHROME_FLATPAK: &str = include_str!("../test/desktop_entries/com.google.Chrome.desktop");
+    const WEBAPP: &str = include_str!("../test/desktop_entries/webapp-proton.desktop");
+
+    fn xdg_dirs(root: &Path) -> XdgDirs {
+        let dirs = XdgDirs {
+            config_dirs: vec![root.join("config"), root.join("etc/xdg")],
+            data_dirs: vec![root.join("data"), root.join("usr/share")],
+            desktops: vec!["gnome".to_string()],
+        };
+        let system = root.join("usr/share/applications");
+        std::fs::create_dir_all(system.join("vendor")).unwrap();
+        std::fs::write(system.join("google-chrome.desktop"), CHROME).unwrap();
+        std::fs::write(system.join("firefox.desktop"), FIREFOX).unwrap();
+        std::fs::write(system.join("vendor/proton.desktop"), WEBAPP).unwrap();
+        std::fs::create_dir_all(root.join("config")).unwrap();
+        dirs
+    }
+
+    #[test]
+    fn test_detect() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = xdg_dirs(root.path());
+        std::fs::write(
+            root.path().join("config/mimeapps.list"),
+            "[Default Applications]\nx-scheme-handler/https=missing.desktop;firefox.desktop;\n",
+        )
+        .unwrap();
+
+        let browsers = detect(&dirs);
+        assert_eq!(
+            browsers,
+            vec![
+                Browser {
+                    id: "firefox.desktop".to_string(),
+                    name: "Firefox".to_string(),
+                    command: BrowserCommand::parse("/usr/lib/firefox/firefox").unwrap(),
+                    family: BrowserFamily::Firefox,
+                    default: true,
+                },
+                Browser {
+                    id: "google-chrome.desktop".to_string(),
+                    name: "Google Chrome".to_string(),
+                    command: BrowserCommand::parse("/usr/bin/google-chrome-stable").unwrap(),
+                    family: BrowserFamily::Chromium,
+                    default: false,
+                },
+            ]
+        );
+
+        // the default browser is used when it can run apps
+        assert_eq!(fallback(&dirs).unwrap().id, "firefox.desktop".to_string());
+
+        // otherwise Chromium-based browsers come first
+        std::fs::write(root.path().join("config/mimeapps.list"), "").unwrap();
+        assert_eq!(
+            fallback(&dirs).unwrap().id,
+            "google-chrome.desktop".to_string()
+        );
+    }
+
+    #[test]
+    fn test_default_browser_lookup_order() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = xdg_dirs(root.path());
+        assert_eq!(default_browser_id(&dirs), None);
+
+        std::fs::write(
+            root.path().join("usr/share/applications/mimeapps.list"),
+            "[Default Applications]\ntext/html=firefox.desktop\n",
+        )
+        .unwrap();
+        assert_eq!(
+            default_browser_id(&dirs).as_deref(),
+            Some("firefox.desktop")
+        );
+
+        // the https handler wins over text/html, wherever it is set
+        std::fs::create_dir_all(root.path().join("etc/xdg")).unwrap();
+        std::fs::write(
+            root.path().join("etc/xdg/mimeapps.list"),
+            "[Default Applications]\nx-scheme-handler/https=google-chrome.desktop\n",
+        )
+        .unwrap();
+        assert_eq!(
+            default_browser_id(&dirs).as_deref(),
+            Some("google-chrome.desktop")
+        );
+
+        // desktop specific lists come before the generic one in the same directory
+        std::fs::write(
+            root.path().join("config/mimeapps.list"),
+            "[Default Applications]\nx-scheme-handler/https=google-chrome.desktop\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("config/gnome-mimeapps.list"),
+            "[Default Applications]\nx-scheme-handler/https=firefox.desktop\n",
+        )
+        .unwrap();
+        assert_eq!(
+            default_browser_id(&dirs).as_deref(),
+            Some("firefox.desktop")
+        );
+    }
+
+    #[test]
+    fn test_desktop_file_ids() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = xdg_dirs(root.path());
+        // a user entry hides the system one with the same id
+        std::fs::create_dir_all(root.path().join("data/applications")).unwrap();
+        std::fs::write(
+            root.path().join("data/applications/firefox.desktop"),
+            "[Desktop Entry]\nType=Application\nName=Firefox\nExec=firefox %u\nHidden=true\n",
+        )
+        .unwrap();
+
+        let ids: Vec<String> = desktop_files(&dirs).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "firefox.desktop",
+                "google-chrome.desktop",
+                "vendor-proton.desktop"
+            ]
+        );
+        let names: Vec<String> = detect(&dirs).into_iter().map(|b| b.name).collect();
+        assert_eq!(names, vec!["Google Chrome"]);
+    }
+
+    #[test]
+    fn test_sandboxed_browsers() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = xdg_dirs(root.path());
+        let flatpak_apps = root.path().join("data/flatpak/exports/share/applications");
+        std::fs::create_dir_all(&flatpak_apps).unwrap();
+        std::fs::write(
+            flatpak_apps.join("com.google.Chrome.desktop"),
+            CHROME_FLATPAK,
+        )
+        .unwrap();
+        let dirs = XdgDirs {
+            data_dirs: vec![root.path().join("data/flatpak/exports/share")]
+                .into_iter()
+                .chain(dirs.data_dirs)
+                .collect(),
+            ..dirs
+        };
+
+        let chrome = detect(&dirs)
+            .into_iter()
+            .find(|b| b.id == "com.google.Chrome.desktop")
+            .unwrap();
+        assert_eq!(chrome.family, BrowserFamily::Chromium);
+        assert_eq!(
+            chrome.command,
+            BrowserCommand {
+                program: "/usr/bin/flatpak".to_string(),
+                args: vec![
+                    "run".to_string(),
+                    "--branch=stable".to_string(),
+                    "--arch=x86_64".to_string(),
+                    "--command=/app/bin/chrome".to_string(),
+                    "com.google.Chrome".to_string(),
+                ],
+                sandbox: Some(Sandbox::Flatpak("com.google.Chrome".to_string())),
+            }
+        );
+        assert_eq!(
+            chrome.command.to_string(),
+            "/usr/bin/flatpak run --branch=stable --arch=x86_64 --command=/app/bin/chrome com.google.Chrome"
+        );
+        assert_eq!(
+            chrome.command.profile_root(Path::new("/home/me")),
+            Some(PathBuf::from(
+                "/home/me/.var/app/com.google.Chrome/data/tarantula"
+            ))
+        );
+
+        let wrapper =
+            BrowserCommand::parse("/var/lib/flatpak/exports/bin/com.brave.Browser").unwrap();
+        assert_eq!(
+            wrapper.sandbox,
+            Some(Sandbox::Flatpak("com.brave.Browser".to_string()))
+        );
+        assert_eq!(wrapper.family(), BrowserFamily::Chromium);
+        assert_eq!(
+            BrowserCommand::parse("flatpak run org.mozilla.firefox")
+                .unwrap()
+                .family(),
+            BrowserFamily::Firefox
+        );
+
+        let snap = BrowserCommand::parse(
+            "env BAMF_DESKTOP_FILE_HINT=/var/lib/snapd/desktop/applications/chromium_chromium.desktop /snap/bin/chromium %U",
+        )
+        .unwrap();
+        assert_eq!(snap.program, "/snap/bin/chromium");
+        assert!(snap.args.is_empty());
+        assert_eq!(snap.family(), BrowserFamily::Chromium);
+        assert_eq!(
+            snap.profile_root(Path::new("/home/me")),
+            Some(PathBuf::from("/home/me/snap/chromium/common/tarantula"))
+        );
+        assert_eq!(
+            BrowserCommand::parse("/usr/bin/chromium")
+                .unwrap()
+                .profile_root(Path::new("/home/me")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            parse_version("Google Chrome 120.0.6099.109 \n").as_deref(),
+            Some("120.0.6099.109")
+        );
+        assert_eq!(
+            parse_version("Mozilla Firefox 121.0\n").as_deref(),
+            Some("121.0")
+        );
+        assert_eq!(
+            parse_version("Brave Browser 120.1.61.104").as_deref(),
+            Some("120.1.61.104")
+        );
+        assert_eq!(parse_version("unknown option --version"), None);
+        assert_eq!(family("/usr/bin/brave-browser"), BrowserFamily::Chromium);
+        assert_eq!(family("librewolf"), BrowserFamily::Firefox);
+        assert_eq!(family("epiphany"), BrowserFamily::Epiphany);
+        assert_eq!(family("falkon"), BrowserFamily::Other);
+    }
+}
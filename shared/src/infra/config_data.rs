@@ -1,4 +1,5 @@
 use crate::app::config;
+use crate::domain::app::{CommandTemplate, SessionFlags};
 use std::io::Write;
 
 pub enum ConfigDataError {
@@ -16,7 +17,71 @@ pub fn update_browser_path(
     Ok(())
 }
 
+pub fn update_compositors(
+    compositors: Vec<config::Compositor>,
+    config: &mut config::Config,
+) -> Result<(), ConfigDataError> {
+    config.compositors = compositors;
+
+    update_config_file(config).map_err(ConfigDataError::Io)?;
+
+    Ok(())
+}
+
+pub fn update_launcher_command(
+    launcher_command: Option<String>,
+    config: &mut config::Config,
+) -> Result<(), ConfigDataError> {
+    config.launcher_command = launcher_command;
+
+    update_config_file(config).map_err(ConfigDataError::Io)?;
+
+    Ok(())
+}
+
+pub fn update_command_template(
+    command_template: CommandTemplate,
+    config: &mut config::Config,
+) -> Result<(), ConfigDataError> {
+    config.command_template = command_template;
+
+    update_config_file(config).map_err(ConfigDataError::Io)?;
+
+    Ok(())
+}
+
+pub fn update_session_flags(
+    session_flags: SessionFlags,
+    config: &mut config::Config,
+) -> Result<(), ConfigDataError> {
+    config.session_flags = session_flags;
+
+    update_config_file(config).map_err(ConfigDataError::Io)?;
+
+    Ok(())
+}
+
 pub fn get_browser_path(config: &config::Config) -> Result<Option<String>, std::io::Error> {
+    Ok(read_config_file(config)?.browser_path)
+}
+
+pub fn get_compositors(config: &config::Config) -> Result<Vec<config::Compositor>, std::io::Error> {
+    Ok(read_config_file(config)?.compositors)
+}
+
+pub fn get_launcher_command(config: &config::Config) -> Result<Option<String>, std::io::Error> {
+    Ok(read_config_file(config)?.launcher_command)
+}
+
+pub fn get_command_template(config: &config::Config) -> Result<CommandTemplate, std::io::Error> {
+    Ok(read_config_file(config)?.command_template)
+}
+
+pub fn get_session_flags(config: &config::Config) -> Result<SessionFlags, std::io::Error> {
+    Ok(read_config_file(config)?.session_flags)
+}
+
+fn read_config_file(config: &config::Config) -> Result<config::Config, std::io::Error> {
     let file = match std::fs::File::open(config.app_data_path.join("config.json")) {
         Ok(f) => f,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
@@ -32,7 +97,7 @@ pub fn get_browser_path(config: &config::Config) -> Result<Option<String>, std::
     };
     let reader = std::io::BufReader::new(file);
     let config: config::Config = serde_json::from_reader(reader)?;
-    Ok(config.browser_path)
+    Ok(config)
 }
 
 fn update_config_file(config: &config::Config) -> Result<(), std::io::Error> {
@@ -1,47 +1,476 @@
 use crate::app::config::Config;
-use crate::domain::app::App;
-use std::fs::File;
-use std::io::Write;
+use crate::domain::app::{App, AppAction};
+use crate::domain::app_name::{AppName, AppNameError};
+use crate::infra::app_data;
+use crate::infra::desktop_entry::{self, DesktopEntryError, DesktopFile, MAIN_GROUP, quote_exec};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub enum DesktopDataError {
     Io(std::io::Error),
+    AppData(app_data::ProjectDataError),
+    Entry(DesktopEntryError),
+    InvalidName(AppNameError),
+    // an entry whose recovery keys can't be read back into an app
+    InvalidData(String),
+}
+
+// Keys that let the registry be rebuilt from the entries if apps.json is lost.
+const ID_KEY: &str = "X-Tarantula-Id";
+const URL_KEY: &str = "X-Tarantula-URL";
+// the rest of the app as JSON
+const OPTIONS_KEY: &str = "X-Tarantula-Options";
+
+const MENU_NAME: &str = "Web Apps";
+const MENU_HEADER: &str = "<!-- Generated by tarantula, do not edit. -->";
+
+#[derive(Debug, Clone)]
+pub struct DesktopEntry {
+    pub path: PathBuf,
+    pub fields: HashMap<String, String>,
+}
+
+impl DesktopEntry {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(|v| v.as_str())
+    }
+
+    // The Exec arguments, with field codes left in place.
+    pub fn exec(&self) -> Vec<String> {
+        self.get("Exec")
+            .and_then(|exec| desktop_entry::parse_exec(exec).ok())
+            .unwrap_or_default()
+    }
 }
 
 pub fn create_entry(app: &App, config: &Config) -> Result<(), DesktopDataError> {
-    let file_name = app.name.to_lowercase().replace(' ', "_");
-    let file_name = format!("{}.desktop", file_name);
-    let path = config.desktop_data_path.clone().join(file_name);
-    let mut file = File::create(path).map_err(DesktopDataError::Io)?;
-
-    let mut content = format!(
-        "[Desktop Entry]\nName={}\nExec=tarantula {}\nType=Application\n",
-        app.name,
-        app.name.to_lowercase().replace(" ", "_")
-    );
-    if app.icon.is_some() {
-        content.push_str(&format!("Icon={}\n", app.icon.as_ref().unwrap()));
+    let name = AppName::parse(&app.name).map_err(DesktopDataError::InvalidName)?;
+    let path = entry_path(&name, config);
+    std::fs::create_dir_all(&config.desktop_data_path).map_err(DesktopDataError::Io)?;
+
+    let slug = name.slug();
+    let mut file = DesktopFile::new();
+    let entry = file.group_mut(MAIN_GROUP);
+    entry.set("Name", name.as_str());
+    entry.set("Exec", &quote_exec(&[config.launcher(), &slug]));
+    entry.set("Type", "Application");
+    entry.set("StartupWMClass", &app.window_class());
+    entry.set("StartupNotify", "true");
+    if let Some(icon) = &app.icon {
+        entry.set("Icon", icon);
+    }
+    for (locale, name) in &app.localized_names {
+        entry.set(&format!("Name[{}]", locale), name);
+    }
+    if let Some(description) = &app.description {
+        entry.set("Comment", description);
+    }
+    if !app.keywords.is_empty() {
+        entry.set_list("Keywords", &app.keywords);
+    }
+    if !app.categories.is_empty() {
+        entry.set_list("Categories", &app.categories);
     }
+    if app.hidden {
+        entry.set("NoDisplay", "true");
+    }
+    entry.set(ID_KEY, &app.name);
+    entry.set(URL_KEY, &app.url);
+    entry.set(OPTIONS_KEY, &app_options(app)?);
 
-    file.write_all(content.as_bytes())
-        .map_err(DesktopDataError::Io)?;
+    let ids = action_ids(&app.actions);
+    if !ids.is_empty() {
+        entry.set_list("Actions", &ids);
+    }
+    for (id, action) in ids.iter().zip(&app.actions) {
+        let group = file.group_mut(&format!("Desktop Action {}", id));
+        group.set("Name", &action.label);
+        group.set(
+            "Exec",
+            &quote_exec(&[config.launcher(), &slug, &action.target]),
+        );
+    }
+
+    file.write(&path).map_err(DesktopDataError::Entry)?;
 
     Ok(())
 }
 
+fn app_options(app: &App) -> Result<String, DesktopDataError> {
+    let mut options =
+        serde_json::to_value(app).map_err(|e| DesktopDataError::InvalidData(e.to_string()))?;
+    if let Some(options) = options.as_object_mut() {
+        options.remove("name");
+        options.remove("url");
+    }
+    Ok(options.to_string())
+}
+
+// Reads the app back from the recovery keys, or None for entries written without them.
+pub fn app_from_entry(entry: &DesktopEntry) -> Result<Option<App>, DesktopDataError> {
+    let (name, url) = match (entry.get(ID_KEY), entry.get(URL_KEY)) {
+        (Some(name), Some(url)) => (name, url),
+        _ => return Ok(None),
+    };
+
+    let invalid = |e: serde_json::Error| {
+        DesktopDataError::InvalidData(format!("{}: {}", entry.path.display(), e))
+    };
+    let mut options: serde_json::Value =
+        serde_json::from_str(entry.get(OPTIONS_KEY).unwrap_or("{}")).map_err(invalid)?;
+    let object = options.as_object_mut().ok_or_else(|| {
+        DesktopDataError::InvalidData(format!(
+            "{}: {} is not an object",
+            entry.path.display(),
+            OPTIONS_KEY
+        ))
+    })?;
+    object.insert("name".to_string(), name.into());
+    object.insert("url".to_string(), url.into());
+
+    serde_json::from_value(options).map(Some).map_err(invalid)
+}
+
+// The slug of the app an entry launches, if it's an entry we wrote: one carrying the
+// app's name, or from versions that didn't write it, one named after the slug it
+// launches. Entries users wrote to run tarantula, e.g. with a URL or options, aren't ours.
+pub fn launched_app(entry: &DesktopEntry, config: &Config) -> Option<String> {
+    let exec = entry.exec();
+    let command = exec.first()?;
+    let file_name = Path::new(command).file_name().and_then(|f| f.to_str());
+    if command != config.launcher() && file_name != Some("tarantula") {
+        return None;
+    }
+    if let Some(name) = entry.get(ID_KEY) {
+        return AppName::parse(name).ok().map(|name| name.slug());
+    }
+    let slug = exec.get(1)?;
+    let stem = entry.path.file_stem().and_then(|s| s.to_str());
+    (exec.len() == 2 && stem == Some(slug.as_str())).then(|| slug.clone())
+}
+
+// Action identifiers may only use letters, digits and dashes, so they are derived from the labels.
+fn action_ids(actions: &[AppAction]) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for action in actions {
+        let base: String = action
+            .label
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let base = match base.trim_matches('-') {
+            "" => "action".to_string(),
+            base => base.to_string(),
+        };
+
+        let mut id = base.clone();
+        let mut n = 2;
+        while ids.contains(&id) {
+            id = format!("{}-{}", base, n);
+            n += 1;
+        }
+        ids.push(id);
+    }
+    ids
+}
+
 pub fn update_entry(app: &App, config: &Config) -> Result<(), DesktopDataError> {
     // For simplicity, we'll just recreate the entry
     create_entry(app, config)
 }
 
-pub fn remove_entry(app_name: &str, config: &Config) -> Result<(), DesktopDataError> {
-    let file_name = app_name.to_lowercase().replace(' ', "_");
-    let file_name = format!("{}.desktop", file_name);
-    let path = config.desktop_data_path.clone().join(file_name);
+pub fn remove_entry(app_name: &AppName, config: &Config) -> Result<(), DesktopDataError> {
+    let path = entry_path(app_name, config);
     std::fs::remove_file(path).map_err(DesktopDataError::Io)?;
     Ok(())
 }
 
+// Writes the app's XDG autostart entry, or removes it when autostart is off.
+pub fn update_autostart_entry(app: &App, config: &Config) -> Result<(), DesktopDataError> {
+    let name = AppName::parse(&app.name).map_err(DesktopDataError::InvalidName)?;
+    let autostart = match &app.autostart {
+        Some(autostart) => autostart,
+        None => return remove_autostart_entry(&name, config),
+    };
+
+    let mut args = vec![config.launcher().to_string()];
+    if let Some(delay) = autostart.delay {
+        args.push("--delay".to_string());
+        args.push(delay.to_string());
+    }
+    if autostart.minimized {
+        args.push("--minimized".to_string());
+    }
+    args.push(name.slug());
+
+    let path = autostart_path(&name, config);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(DesktopDataError::Io)?;
+    }
+
+    let mut file = DesktopFile::new();
+    let entry = file.group_mut(MAIN_GROUP);
+    entry.set("Name", name.as_str());
+    entry.set(
+        "Exec",
+        &quote_exec(&args.iter().map(String::as_str).collect::<Vec<_>>()),
+    );
+    entry.set("Type", "Application");
+    entry.set("StartupWMClass", &app.window_class());
+    if let Some(icon) = &app.icon {
+        entry.set("Icon", icon);
+    }
+
+    file.write(&path).map_err(DesktopDataError::Entry)?;
+
+    Ok(())
+}
+
+pub fn remove_autostart_entry(app_name: &AppName, config: &Config) -> Result<(), DesktopDataError> {
+    match std::fs::remove_file(autostart_path(app_name, config)) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(DesktopDataError::Io(e)),
+    }
+}
+
+// Autostart entries carry a prefix so they don't replace the entry of a native app with the same name.
+pub fn autostart_path(app_name: &AppName, config: &Config) -> PathBuf {
+    config
+        .xdg_config_path
+        .join("autostart")
+        .join(format!("tarantula-{}.desktop", app_name.slug()))
+}
+
+pub fn entry_path(app_name: &AppName, config: &Config) -> PathBuf {
+    config
+        .desktop_data_path
+        .join(format!("{}.desktop", app_name.slug()))
+}
+
+// Reads the [Desktop Entry] group of every .desktop file in `dir`.
+// Files that can't be read are skipped so one broken entry doesn't hide the rest.
+pub fn read_entries(dir: &Path) -> Result<Vec<DesktopEntry>, DesktopDataError> {
+    let read_dir = match std::fs::read_dir(dir) {
+        Ok(r) => r,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(DesktopDataError::Io(e)),
+    };
+
+    let mut entries = Vec::new();
+    for dir_entry in read_dir {
+        let path = dir_entry.map_err(DesktopDataError::Io)?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("desktop") {
+            continue;
+        }
+        if let Ok(entry) = read_entry(&path) {
+            entries.push(entry);
+        }
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(entries)
+}
+
+pub fn read_entry(path: &Path) -> Result<DesktopEntry, DesktopDataError> {
+    let file = DesktopFile::read(path).map_err(DesktopDataError::Entry)?;
+
+    let mut fields = HashMap::new();
+    if let Some(group) = file.group(MAIN_GROUP) {
+        for (key, value) in group.entries() {
+            fields.entry(key.to_string()).or_insert(value);
+        }
+    }
+
+    Ok(DesktopEntry {
+        path: path.to_path_buf(),
+        fields,
+    })
+}
+
+// Writes a [Desktop Entry] group with the given keys, in order.
+pub fn write_entry(path: &Path, fields: &[(&str, &str)]) -> Result<(), DesktopDataError> {
+    let mut file = DesktopFile::new();
+    let entry = file.group_mut(MAIN_GROUP);
+    for (key, value) in fields {
+        entry.set(key, value);
+    }
+
+    file.write(path).map_err(DesktopDataError::Entry)?;
+
+    Ok(())
+}
+
+// Regenerates the XDG menu fragment that groups all apps in a "Web Apps" submenu,
+// with a nested submenu per tag. Everything is removed once no apps are left.
+pub fn sync_menu(config: &Config) -> Result<(), DesktopDataError> {
+    let apps = app_data::get_apps(config).map_err(DesktopDataError::AppData)?;
+    remove_menu(&apps, config)?;
+    if apps.is_empty() {
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(&config.desktop_directory_path).map_err(DesktopDataError::Io)?;
+    write_entry(
+        &config.desktop_directory_path.join(directory_file("")),
+        &[
+            ("Type", "Directory"),
+            ("Name", MENU_NAME),
+            ("Icon", "applications-internet"),
+        ],
+    )?;
+    for tag in menu_tags(&apps) {
+        write_entry(
+            &config.desktop_directory_path.join(directory_file(&tag)),
+            &[("Type", "Directory"), ("Name", &tag), ("Icon", "folder")],
+        )?;
+    }
+
+    let path = menu_path(config);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(DesktopDataError::Io)?;
+    }
+    std::fs::write(path, generate_menu(&apps, config)).map_err(DesktopDataError::Io)?;
+
+    Ok(())
+}
+
+pub fn menu_path(config: &Config) -> PathBuf {
+    config
+        .xdg_config_path
+        .join("menus/applications-merged/tarantula.menu")
+}
+
+// Removes the menu and the directory files written for it, for the tags of the menu
+// being replaced and the apps' current ones. Other directory files may be the user's.
+fn remove_menu(apps: &[App], config: &Config) -> Result<(), DesktopDataError> {
+    let path = menu_path(config);
+    let mut tags = match std::fs::read_to_string(&path) {
+        Ok(menu) if menu.contains(MENU_HEADER) => generated_tags(&menu),
+        Ok(_) => Vec::new(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(DesktopDataError::Io(e)),
+    };
+    tags.extend(menu_tags(apps));
+
+    match std::fs::remove_file(&path) {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(DesktopDataError::Io(e)),
+    }
+
+    let file_names =
+        std::iter::once(directory_file("")).chain(tags.iter().map(|t| directory_file(t)));
+    for file_name in file_names {
+        match std::fs::remove_file(config.desktop_directory_path.join(file_name)) {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(DesktopDataError::Io(e)),
+        }
+    }
+
+    Ok(())
+}
+
+// Tags of the submenus in a menu written by generate_menu.
+fn generated_tags(menu: &str) -> Vec<String> {
+    menu.lines()
+        .filter_map(|line| line.trim().strip_prefix("<Name>")?.strip_suffix("</Name>"))
+        .filter(|name| *name != "Applications" && *name != MENU_NAME)
+        .map(|name| {
+            name.replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&")
+        })
+        .collect()
+}
+
+fn generate_menu(apps: &[App], config: &Config) -> String {
+    let mut menu = String::from(
+        "<!DOCTYPE Menu PUBLIC \"-//freedesktop//DTD Menu 1.0//EN\"\n \"http://www.freedesktop.org/standards/menu-spec/1.0/menu.dtd\">\n",
+    );
+    menu.push_str(MENU_HEADER);
+    menu.push_str("\n<Menu>\n  <Name>Applications</Name>\n  <Menu>\n");
+    menu.push_str(&format!("    <Name>{}</Name>\n", MENU_NAME));
+    menu.push_str(&format!(
+        "    <Directory>{}</Directory>\n",
+        directory_file("")
+    ));
+
+    let untagged: Vec<&App> = apps.iter().filter(|a| a.tags.is_empty()).collect();
+    push_includes(&mut menu, &untagged, config, "    ");
+
+    for tag in menu_tags(apps) {
+        let tagged: Vec<&App> = apps.iter().filter(|a| a.tags.contains(&tag)).collect();
+        menu.push_str("    <Menu>\n");
+        menu.push_str(&format!("      <Name>{}</Name>\n", xml_escape(&tag)));
+        menu.push_str(&format!(
+            "      <Directory>{}</Directory>\n",
+            xml_escape(&directory_file(&tag))
+        ));
+        push_includes(&mut menu, &tagged, config, "      ");
+        menu.push_str("    </Menu>\n");
+    }
+
+    menu.push_str("  </Menu>\n</Menu>\n");
+    menu
+}
+
+fn push_includes(menu: &mut String, apps: &[&App], config: &Config, indent: &str) {
+    if apps.is_empty() {
+        return;
+    }
+    menu.push_str(&format!("{}<Include>\n", indent));
+    // apps with invalid names have no desktop entry
+    for name in apps.iter().filter_map(|a| AppName::parse(&a.name).ok()) {
+        let path = entry_path(&name, config);
+        let file_name = path.file_name().and_then(|f| f.to_str()).unwrap_or("");
+        menu.push_str(&format!(
+            "{}  <Filename>{}</Filename>\n",
+            indent,
+            xml_escape(file_name)
+        ));
+    }
+    menu.push_str(&format!("{}</Include>\n", indent));
+}
+
+fn menu_tags(apps: &[App]) -> Vec<String> {
+    let mut tags: Vec<String> = apps.iter().flat_map(|a| a.tags.clone()).collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+// Tags that only differ in case or punctuation get the same slug, so the file name
+// ends in a hash of the tag to keep their directory files apart.
+fn directory_file(tag: &str) -> String {
+    if tag.is_empty() {
+        return "tarantula.directory".to_string();
+    }
+    let slug: String = tag
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("tarantula-{}-{:08x}.directory", slug, fnv1a(tag))
+}
+
+// 32-bit FNV-1a, which unlike std's hashers stays the same across Rust versions.
+fn fnv1a(value: &str) -> u32 {
+    value.bytes().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    })
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,6 +483,7 @@ mod tests {
             name: "Test App".to_string(),
             url: "http://example.com".to_string(),
             icon: Some("test_icon".to_string()),
+            ..Default::default()
         };
         create_entry(&app, &config).unwrap();
         let file_name = "test_app.desktop";
@@ -61,7 +491,225 @@ mod tests {
         assert!(path.exists());
 
         let file = std::fs::read_to_string(path).unwrap();
-        let expected_content = "[Desktop Entry]\nName=Test App\nExec=tarantula test_app\nType=Application\nIcon=test_icon\n";
+        let expected_content = "[Desktop Entry]\nName=Test App\nExec=tarantula test_app\nType=Application\nStartupWMClass=tarantula-test_app\nStartupNotify=true\nIcon=test_icon\nX-Tarantula-Id=Test App\nX-Tarantula-URL=http://example.com\nX-Tarantula-Options={\"icon\":\"test_icon\"}\n";
         assert_eq!(file, expected_content);
     }
+
+    #[test]
+    fn test_create_entry_metadata() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let app = App {
+            name: "Mail".to_string(),
+            url: "https://mail.example.com".to_string(),
+            description: Some("Read your mail\nanywhere".to_string()),
+            keywords: vec!["email".to_string(), "inbox;post".to_string()],
+            categories: vec!["Network".to_string(), "Email".to_string()],
+            localized_names: [
+                ("de".to_string(), "Post".to_string()),
+                ("pt_BR".to_string(), "Correio".to_string()),
+            ]
+            .into(),
+            hidden: true,
+            ..Default::default()
+        };
+        create_entry(&app, &config).unwrap();
+
+        let file = std::fs::read_to_string(config.desktop_data_path.join("mail.desktop")).unwrap();
+        assert!(file.contains(
+            "Name[de]=Post\nName[pt_BR]=Correio\nComment=Read your mail\\nanywhere\nKeywords=email;inbox\\;post;\nCategories=Network;Email;\nNoDisplay=true\nX-Tarantula-Id=Mail\n"
+        ));
+    }
+
+    #[test]
+    fn test_app_from_entry() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let app = App {
+            name: "Mail".to_string(),
+            url: "https://mail.example.com/?a=1;b=2".to_string(),
+            description: Some("Read your mail\nanywhere".to_string()),
+            tags: vec!["Work".to_string()],
+            hidden: true,
+            actions: vec![AppAction {
+                label: "Inbox".to_string(),
+                target: "/inbox".to_string(),
+            }],
+            ..Default::default()
+        };
+        create_entry(&app, &config).unwrap();
+
+        let entry = read_entry(&config.desktop_data_path.join("mail.desktop")).unwrap();
+        let recovered = app_from_entry(&entry).unwrap().unwrap();
+        assert_eq!(
+            serde_json::to_value(&recovered).unwrap(),
+            serde_json::to_value(&app).unwrap()
+        );
+        assert_eq!(launched_app(&entry, &config), Some("mail".to_string()));
+
+        // entries users wrote themselves
+        for (file_name, exec) in [
+            ("news.desktop", "tarantula https://news.example.com"),
+            ("mail-minimized.desktop", "tarantula --minimized mail"),
+            ("inbox.desktop", "tarantula mail /inbox"),
+        ] {
+            let path = config.desktop_data_path.join(file_name);
+            write_entry(&path, &[("Name", "Mine"), ("Exec", exec)]).unwrap();
+            assert_eq!(launched_app(&read_entry(&path).unwrap(), &config), None);
+        }
+
+        let path = config.desktop_data_path.join("old.desktop");
+        write_entry(&path, &[("Name", "Old"), ("Exec", "tarantula old")]).unwrap();
+        assert!(
+            app_from_entry(&read_entry(&path).unwrap())
+                .unwrap()
+                .is_none()
+        );
+
+        write_entry(
+            &path,
+            &[
+                ("X-Tarantula-Id", "Old"),
+                ("X-Tarantula-URL", "https://example.com"),
+                ("X-Tarantula-Options", "{\"tags\":"),
+            ],
+        )
+        .unwrap();
+        assert!(matches!(
+            app_from_entry(&read_entry(&path).unwrap()),
+            Err(DesktopDataError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_create_entry_rejects_invalid_names() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        for name in ["Mail\nExec=sh -c reboot", "../../../.bashrc", "a/b"] {
+            let app = App {
+                name: name.to_string(),
+                url: "https://example.com".to_string(),
+                ..Default::default()
+            };
+            assert!(matches!(
+                create_entry(&app, &config),
+                Err(DesktopDataError::InvalidName(_))
+            ));
+        }
+        assert_eq!(
+            std::fs::read_dir(&config.desktop_data_path)
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_autostart_entry() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let mut app = App {
+            name: "Proton Mail".to_string(),
+            url: "https://mail.proton.me".to_string(),
+            autostart: Some(crate::domain::app::Autostart {
+                delay: Some(10),
+                minimized: true,
+            }),
+            ..Default::default()
+        };
+        update_autostart_entry(&app, &config).unwrap();
+
+        let path = config
+            .xdg_config_path
+            .join("autostart/tarantula-proton_mail.desktop");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[Desktop Entry]\nName=Proton Mail\nExec=tarantula --delay 10 --minimized proton_mail\nType=Application\nStartupWMClass=tarantula-proton_mail\n"
+        );
+
+        app.autostart = None;
+        update_autostart_entry(&app, &config).unwrap();
+        assert!(!path.exists());
+        // removing an entry that is already gone is fine
+        update_autostart_entry(&app, &config).unwrap();
+    }
+
+    #[test]
+    fn test_sync_menu() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let gmail = App {
+            name: "Gmail".to_string(),
+            url: "https://mail.google.com".to_string(),
+            ..Default::default()
+        };
+        let slack = App {
+            name: "Slack".to_string(),
+            url: "https://app.slack.com".to_string(),
+            tags: vec!["Work & Chat".to_string()],
+            ..Default::default()
+        };
+        let teams = App {
+            name: "Teams".to_string(),
+            url: "https://teams.microsoft.com".to_string(),
+            tags: vec!["Work - Chat".to_string()],
+            ..Default::default()
+        };
+        app_data::add_app(gmail.clone(), &config).unwrap();
+        app_data::add_app(slack, &config).unwrap();
+        app_data::add_app(teams, &config).unwrap();
+        sync_menu(&config).unwrap();
+
+        let expected = format!(
+            "<!DOCTYPE Menu PUBLIC \"-//freedesktop//DTD Menu 1.0//EN\"\n \"http://www.freedesktop.org/standards/menu-spec/1.0/menu.dtd\">\n{}\n<Menu>\n  <Name>Applications</Name>\n  <Menu>\n    <Name>Web Apps</Name>\n    <Directory>tarantula.directory</Directory>\n    <Include>\n      <Filename>gmail.desktop</Filename>\n    </Include>\n    <Menu>\n      <Name>Work &amp; Chat</Name>\n      <Directory>tarantula-work---chat-d1217a68.directory</Directory>\n      <Include>\n        <Filename>slack.desktop</Filename>\n      </Include>\n    </Menu>\n    <Menu>\n      <Name>Work - Chat</Name>\n      <Directory>tarantula-work---chat-f5a738db.directory</Directory>\n      <Include>\n        <Filename>teams.desktop</Filename>\n      </Include>\n    </Menu>\n  </Menu>\n</Menu>\n",
+            MENU_HEADER
+        );
+        assert_eq!(
+            std::fs::read_to_string(menu_path(&config)).unwrap(),
+            expected
+        );
+        // tags with the same slug keep directory files of their own
+        for (file_name, tag) in [
+            ("tarantula-work---chat-d1217a68.directory", "Work & Chat"),
+            ("tarantula-work---chat-f5a738db.directory", "Work - Chat"),
+        ] {
+            let directory = read_entry(&config.desktop_directory_path.join(file_name)).unwrap();
+            assert_eq!(directory.get("Name"), Some(tag));
+        }
+
+        // a tag going away removes its directory file
+        app_data::remove_app("Teams", &config).unwrap();
+        sync_menu(&config).unwrap();
+        let directory_path = |file_name: &str| config.desktop_directory_path.join(file_name);
+        assert!(!directory_path("tarantula-work---chat-f5a738db.directory").exists());
+        assert!(directory_path("tarantula-work---chat-d1217a68.directory").exists());
+
+        // the last app going away removes the menu and its directory files, but not
+        // directory files tarantula didn't write
+        std::fs::write(
+            directory_path("tarantula-custom.directory"),
+            "[Desktop Entry]\nType=Directory\nName=Mine\n",
+        )
+        .unwrap();
+        app_data::remove_app("Gmail", &config).unwrap();
+        app_data::remove_app("Slack", &config).unwrap();
+        sync_menu(&config).unwrap();
+        assert!(!menu_path(&config).exists());
+        let file_names: Vec<_> = std::fs::read_dir(&config.desktop_directory_path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(file_names, vec!["tarantula-custom.directory"]);
+    }
+
+    #[test]
+    fn test_read_entries() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let content = "[Desktop Entry]\nName=HEY\nExec=omarchy-launch-webapp https://app.hey.com\n\n[Desktop Action new]\nName=New\n";
+        std::fs::write(config.desktop_data_path.join("HEY.desktop"), content).unwrap();
+        std::fs::write(config.desktop_data_path.join("notes.txt"), "Name=Nope").unwrap();
+
+        let entries = read_entries(&config.desktop_data_path).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].get("Name"), Some("HEY"));
+        assert_eq!(
+            entries[0].get("Exec"),
+            Some("omarchy-launch-webapp https://app.hey.com")
+        );
+    }
 }
@@ -0,0 +1,634 @@
+use std::fmt;
+use std::path::Path;
+
+// Reader and writer for freedesktop Desktop Entry files.
+// https://specifications.freedesktop.org/desktop-entry-spec/latest/
+
+pub const MAIN_GROUP: &str = "Desktop Entry";
+
+#[derive(Debug)]
+pub enum DesktopEntryError {
+    Io(std::io::Error),
+    // line number and content of a line that isn't a group, entry or comment
+    InvalidLine(usize, String),
+    InvalidExec(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DesktopFile {
+    // comments and blank lines before the first group
+    header: Vec<Line>,
+    groups: Vec<Group>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub name: String,
+    lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    // comments and blank lines are kept as they are so files round-trip
+    Verbatim(String),
+    // the key includes the locale, e.g. Name[de], and the value is stored escaped
+    Entry { key: String, value: String },
+}
+
+impl DesktopFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(content: &str) -> Result<Self, DesktopEntryError> {
+        let mut file = Self::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                match file.groups.last_mut() {
+                    Some(group) => group.lines.push(Line::Verbatim(line.to_string())),
+                    None => file.header.push(Line::Verbatim(line.to_string())),
+                }
+                continue;
+            }
+
+            if let Some(name) = trimmed
+                .strip_prefix('[')
+                .and_then(|name| name.strip_suffix(']'))
+            {
+                if name.is_empty() || file.group(name).is_some() {
+                    return Err(DesktopEntryError::InvalidLine(index + 1, line.to_string()));
+                }
+                file.groups.push(Group {
+                    name: name.to_string(),
+                    lines: Vec::new(),
+                });
+                continue;
+            }
+
+            let group = file.groups.last_mut();
+            let entry = line.split_once('=');
+            match (group, entry) {
+                (Some(group), Some((key, value))) if !key.trim().is_empty() => {
+                    group.lines.push(Line::Entry {
+                        key: key.trim().to_string(),
+                        value: value.trim_start().to_string(),
+                    });
+                }
+                _ => return Err(DesktopEntryError::InvalidLine(index + 1, line.to_string())),
+            }
+        }
+
+        Ok(file)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, DesktopEntryError> {
+        let content = std::fs::read_to_string(path).map_err(DesktopEntryError::Io)?;
+        Self::parse(&content)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), DesktopEntryError> {
+        std::fs::write(path, self.to_string()).map_err(DesktopEntryError::Io)
+    }
+
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    // Returns the group, adding it at the end of the file if it doesn't exist yet.
+    pub fn group_mut(&mut self, name: &str) -> &mut Group {
+        let index = match self.groups.iter().position(|g| g.name == name) {
+            Some(index) => index,
+            None => {
+                // keep groups apart with a blank line
+                if let Some(last) = self.groups.last_mut()
+                    && last.lines.last() != Some(&Line::Verbatim(String::new()))
+                {
+                    last.lines.push(Line::Verbatim(String::new()));
+                }
+                self.groups.push(Group {
+                    name: name.to_string(),
+                    lines: Vec::new(),
+                });
+                self.groups.len() - 1
+            }
+        };
+        &mut self.groups[index]
+    }
+}
+
+impl fmt::Display for DesktopFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.header {
+            writeln!(f, "{}", line)?;
+        }
+        for group in &self.groups {
+            writeln!(f, "[{}]", group.name)?;
+            for line in &group.lines {
+                writeln!(f, "{}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Line::Verbatim(line) => write!(f, "{}", line),
+            Line::Entry { key, value } => write!(f, "{}={}", key, value),
+        }
+    }
+}
+
+impl Group {
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.raw(key).map(unescape)
+    }
+
+    // Looks a localized key up the way the spec describes, e.g. for de_DE.UTF-8@euro:
+    // Name[de_DE@euro], Name[de_DE], Name[de@euro], Name[de] and finally Name.
+    pub fn get_localized(&self, key: &str, locale: &str) -> Option<String> {
+        let (locale, modifier) = match locale.split_once('@') {
+            Some((locale, modifier)) => (locale, Some(modifier)),
+            None => (locale, None),
+        };
+        let locale = locale.split('.').next().unwrap_or(locale);
+        let (lang, country) = match locale.split_once('_') {
+            Some((lang, country)) => (lang, Some(country)),
+            None => (locale, None),
+        };
+
+        let mut candidates = Vec::new();
+        if let (Some(country), Some(modifier)) = (country, modifier) {
+            candidates.push(format!("{}_{}@{}", lang, country, modifier));
+        }
+        if let Some(country) = country {
+            candidates.push(format!("{}_{}", lang, country));
+        }
+        if let Some(modifier) = modifier {
+            candidates.push(format!("{}@{}", lang, modifier));
+        }
+        candidates.push(lang.to_string());
+
+        candidates
+            .iter()
+            .find_map(|c| self.get(&format!("{}[{}]", key, c)))
+            .or_else(|| self.get(key))
+    }
+
+    pub fn get_list(&self, key: &str) -> Vec<String> {
+        self.raw(key).map(split_list).unwrap_or_default()
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.raw(key)? {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    // Keys and unescaped values, in file order.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, String)> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Entry { key, value } => Some((key.as_str(), unescape(value))),
+            Line::Verbatim(_) => None,
+        })
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.set_raw(key, escape(value));
+    }
+
+    pub fn set_list(&mut self, key: &str, values: &[String]) {
+        let value = values
+            .iter()
+            .map(|v| format!("{};", escape(v).replace(';', "\\;")))
+            .collect();
+        self.set_raw(key, value);
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.lines
+            .retain(|line| !matches!(line, Line::Entry { key: k, .. } if k == key));
+    }
+
+    fn raw(&self, key: &str) -> Option<&str> {
+        self.lines.iter().find_map(|line| match line {
+            Line::Entry { key: k, value } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    fn set_raw(&mut self, key: &str, value: String) {
+        for line in self.lines.iter_mut() {
+            if let Line::Entry { key: k, value: v } = line
+                && k == key
+            {
+                *v = value;
+                return;
+            }
+        }
+
+        // new keys go before the blank lines and comments that separate groups
+        let index = self
+            .lines
+            .iter()
+            .rposition(|line| matches!(line, Line::Entry { .. }))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        self.lines.insert(
+            index,
+            Line::Entry {
+                key: key.to_string(),
+                value,
+            },
+        );
+    }
+}
+
+// Locales as used in localized keys: lang_COUNTRY.ENCODING@MODIFIER
+pub fn is_valid_locale(locale: &str) -> bool {
+    !locale.is_empty()
+        && locale
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '@'))
+}
+
+fn escape(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r");
+    // leading spaces would be trimmed when reading the value back
+    match escaped.strip_prefix(' ') {
+        Some(rest) => format!("\\s{}", rest),
+        None => escaped,
+    }
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('\\') => result.push('\\'),
+            Some(c) => {
+                result.push('\\');
+                result.push(c);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+// Splits a list value on unescaped semicolons.
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(';') => current.push(';'),
+                Some(next) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => current.push('\\'),
+            },
+            ';' => items.push(unescape(&std::mem::take(&mut current))),
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        items.push(unescape(&current));
+    }
+    items
+}
+
+// Characters that force an Exec argument to be quoted.
+const RESERVED_EXEC_CHARS: &str = " \t\n\"'\\><~|&;$*?#()`";
+
+// Splits an (unescaped) Exec value into arguments, undoing the quoting rules.
+// Field codes such as %U are kept, see expand_exec.
+pub fn parse_exec(exec: &str) -> Result<Vec<String>, DesktopEntryError> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            '\\' if in_quotes => match chars.next() {
+                Some(next) if matches!(next, '"' | '`' | '$' | '\\') => current.push(next),
+                Some(next) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => return Err(DesktopEntryError::InvalidExec(exec.to_string())),
+            },
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err(DesktopEntryError::InvalidExec(exec.to_string()));
+    }
+    if has_arg {
+        args.push(current);
+    }
+
+    Ok(args)
+}
+
+// Builds an Exec value that runs `args` as they are.
+pub fn quote_exec(args: &[&str]) -> String {
+    args.iter()
+        .map(|arg| quote_exec_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn quote_exec_arg(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    if !arg.is_empty() && !arg.chars().any(|c| RESERVED_EXEC_CHARS.contains(c)) {
+        return arg;
+    }
+
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Replaces the field codes in parsed Exec arguments with the files or URLs being opened
+// and the entry's own values. Deprecated and unknown codes are dropped.
+pub fn expand_exec(
+    args: &[String],
+    urls: &[String],
+    group: &Group,
+    path: Option<&Path>,
+) -> Vec<String> {
+    let mut expanded = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "%F" | "%U" => {
+                expanded.extend(urls.iter().cloned());
+                continue;
+            }
+            "%f" | "%u" => {
+                expanded.extend(urls.first().cloned());
+                continue;
+            }
+            "%i" => {
+                if let Some(icon) = group.get("Icon") {
+                    expanded.push("--icon".to_string());
+                    expanded.push(icon);
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let mut result = String::new();
+        let mut chars = arg.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('%') => result.push('%'),
+                Some('f' | 'u') => result.push_str(urls.first().map_or("", |u| u.as_str())),
+                Some('c') => result.push_str(&group.get("Name").unwrap_or_default()),
+                Some('k') => {
+                    result.push_str(&path.map(|p| p.display().to_string()).unwrap_or_default())
+                }
+                _ => {}
+            }
+        }
+        // arguments that were only field codes disappear when there is nothing to expand
+        if result.is_empty() && !arg.is_empty() {
+            continue;
+        }
+        expanded.push(result);
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIREFOX: &str = include_str!("../test/desktop_entries/firefox.desktop");
+    const CHROME: &str = include_str!("../test/desktop_entries/google-chrome.desktop");
+    const CHROME_FLATPAK: &str = include_str!("../test/desktop_entries/com.google.Chrome.desktop");
+    const WEB_APP_MANAGER: &str = include_str!("../test/desktop_entries/webapp-proton.desktop");
+
+    #[test]
+    fn test_round_trip() {
+        for content in [FIREFOX, CHROME, CHROME_FLATPAK, WEB_APP_MANAGER] {
+            assert_eq!(DesktopFile::parse(content).unwrap().to_string(), content);
+        }
+    }
+
+    #[test]
+    fn test_groups_and_localized_keys() {
+        let file = DesktopFile::parse(FIREFOX).unwrap();
+        let main = file.group(MAIN_GROUP).unwrap();
+        assert_eq!(main.get("Name"), Some("Firefox".to_string()));
+        assert_eq!(
+            main.get_localized("GenericName", "de_DE.UTF-8"),
+            Some("Webbrowser".to_string())
+        );
+        assert_eq!(
+            main.get_localized("GenericName", "pt_BR"),
+            Some("Navegador Web".to_string())
+        );
+        assert_eq!(
+            main.get_localized("GenericName", "xx"),
+            Some("Web Browser".to_string())
+        );
+        assert_eq!(
+            main.get_list("Actions"),
+            vec!["new-window", "new-private-window"]
+        );
+        assert_eq!(main.get_bool("StartupNotify"), Some(true));
+
+        let action = file.group("Desktop Action new-private-window").unwrap();
+        assert_eq!(
+            parse_exec(&action.get("Exec").unwrap()).unwrap(),
+            vec!["/usr/lib/firefox/firefox", "--private-window", "%u"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_lines() {
+        assert!(matches!(
+            DesktopFile::parse("Name=No group\n"),
+            Err(DesktopEntryError::InvalidLine(1, _))
+        ));
+        assert!(matches!(
+            DesktopFile::parse("[Desktop Entry]\nName=A\n[Desktop Entry]\n"),
+            Err(DesktopEntryError::InvalidLine(3, _))
+        ));
+        assert!(matches!(
+            DesktopFile::parse("[Desktop Entry]\njust text\n"),
+            Err(DesktopEntryError::InvalidLine(2, _))
+        ));
+    }
+
+    #[test]
+    fn test_write() {
+        let mut file = DesktopFile::new();
+        let main = file.group_mut(MAIN_GROUP);
+        main.set("Name", " Padded\tname");
+        main.set("Comment", "Two\nlines and a \\ backslash");
+        main.set_list("Keywords", &["mail".to_string(), "a;b".to_string()]);
+        main.set(
+            "Exec",
+            &quote_exec(&["tarantula", "my app", "https://example.com/?q=100%"]),
+        );
+        file.group_mut("Desktop Action new").set("Name", "New");
+        file.group_mut(MAIN_GROUP).set("Type", "Application");
+
+        let content = file.to_string();
+        assert_eq!(
+            content,
+            "[Desktop Entry]\nName=\\sPadded\\tname\nComment=Two\\nlines and a \\\\ backslash\nKeywords=mail;a\\;b;\nExec=tarantula \"my app\" \"https://example.com/?q=100%%\"\nType=Application\n\n[Desktop Action new]\nName=New\n"
+        );
+
+        let parsed = DesktopFile::parse(&content).unwrap();
+        let main = parsed.group(MAIN_GROUP).unwrap();
+        assert_eq!(main.get("Name"), Some(" Padded\tname".to_string()));
+        assert_eq!(
+            main.get("Comment"),
+            Some("Two\nlines and a \\ backslash".to_string())
+        );
+        assert_eq!(main.get_list("Keywords"), vec!["mail", "a;b"]);
+    }
+
+    #[test]
+    fn test_exec_quoting() {
+        let args = [
+            "/opt/My Browser/browser",
+            "--app=https://example.com/?a=1&b=2",
+            "say \"hi\" for $5 \\o/",
+            "",
+        ];
+        let exec = quote_exec(&args);
+        assert_eq!(
+            exec,
+            "\"/opt/My Browser/browser\" \"--app=https://example.com/?a=1&b=2\" \"say \\\"hi\\\" for \\$5 \\\\o/\" \"\""
+        );
+        assert_eq!(parse_exec(&exec).unwrap(), args);
+
+        let file = DesktopFile::parse(WEB_APP_MANAGER).unwrap();
+        let exec = file.group(MAIN_GROUP).unwrap().get("Exec").unwrap();
+        assert_eq!(
+            parse_exec(&exec).unwrap(),
+            vec![
+                "chromium",
+                "--app=https://mail.proton.me",
+                "--class=WebApp-proton",
+                "--name=WebApp-proton",
+                "--user-data-dir=/home/user/.local/share/ice/profiles/proton",
+            ]
+        );
+
+        // values go through string escaping on top of the quoting
+        let file = DesktopFile::parse(
+            "[Desktop Entry]\nExec=sh -c \"echo \\\\\"$HOME\\\\\" \\\\\\\\ done\"\n",
+        )
+        .unwrap();
+        let exec = file.group(MAIN_GROUP).unwrap().get("Exec").unwrap();
+        assert_eq!(
+            parse_exec(&exec).unwrap(),
+            vec!["sh", "-c", "echo \"$HOME\" \\ done"]
+        );
+
+        assert!(matches!(
+            parse_exec("browser \"unterminated"),
+            Err(DesktopEntryError::InvalidExec(_))
+        ));
+    }
+
+    #[test]
+    fn test_expand_exec() {
+        let file = DesktopFile::parse(CHROME_FLATPAK).unwrap();
+        let main = file.group(MAIN_GROUP).unwrap();
+        let args = parse_exec(&main.get("Exec").unwrap()).unwrap();
+        let urls = vec![
+            "https://example.com".to_string(),
+            "https://example.org".to_string(),
+        ];
+        assert_eq!(
+            expand_exec(&args, &urls, main, None),
+            vec![
+                "/usr/bin/flatpak",
+                "run",
+                "--branch=stable",
+                "--arch=x86_64",
+                "--command=/app/bin/chrome",
+                "--file-forwarding",
+                "com.google.Chrome",
+                "@@u",
+                "https://example.com",
+                "https://example.org",
+                "@@",
+            ]
+        );
+
+        let args: Vec<String> = ["app", "%i", "--title=%c", "--file=%k", "100%%", "%u", "%d"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        assert_eq!(
+            expand_exec(&args, &[], main, Some(Path::new("/apps/chrome.desktop"))),
+            vec![
+                "app",
+                "--icon",
+                "com.google.Chrome",
+                "--title=Google Chrome",
+                "--file=/apps/chrome.desktop",
+                "100%",
+            ]
+        );
+    }
+}
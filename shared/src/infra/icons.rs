@@ -1,5 +1,6 @@
 use crate::app::config;
 use crate::domain::app::App;
+use crate::domain::app_name::{AppName, AppNameError};
 use std::fs;
 use std::path::Path;
 use url::Url;
@@ -8,9 +9,11 @@ use url::Url;
 pub enum StoreIconError {
     Reqwest(reqwest::Error),
     Io(std::io::Error),
+    InvalidName(AppNameError),
 }
 
 pub fn store_icon(app: &App, config: &config::Config) -> Result<Option<String>, StoreIconError> {
+    let name = AppName::parse(&app.name).map_err(StoreIconError::InvalidName)?;
     let url = Url::parse(&app.url).unwrap();
     let url = url.host_str().unwrap();
 
@@ -20,7 +23,7 @@ pub fn store_icon(app: &App, config: &config::Config) -> Result<Option<String>,
     };
 
     let path = Path::new(&config.app_data_path)
-        .join(format!("{}.ico", app.name.to_lowercase().replace(" ", "_")));
+        .join(format!("{}.ico", name.slug()));
 
     fs::write(&path, &bytes).map_err(|e| StoreIconError::Io(e))?;
 
@@ -82,6 +85,7 @@ mod test {
             name: "Perplexity".to_string(),
             url: "https://www.perplexity.ai/".to_string(),
             icon: None,
+            ..Default::default()
         };
 
         let icon_path = store_icon(&app, &config).unwrap().unwrap();
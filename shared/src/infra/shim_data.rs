@@ -0,0 +1,127 @@
+use crate::app::config::Config;
+use crate::domain::app::App;
+use crate::domain::app_name::{AppName, AppNameError};
+use crate::utils;
+use std::path::PathBuf;
+
+const MARKER: &str = "# Generated by tarantula";
+
+#[derive(Debug)]
+pub enum ShimDataError {
+    Io(std::io::Error),
+    // a command that isn't one of our shims already uses the name
+    CommandExists(PathBuf),
+    InvalidName(AppNameError),
+}
+
+pub fn shim_name(app_name: &AppName) -> String {
+    app_name.slug().replace('_', "-")
+}
+
+pub fn shim_path(app_name: &AppName, config: &Config) -> PathBuf {
+    config.bin_path.join(shim_name(app_name))
+}
+
+// Fails if the shim would shadow a command that is already on $PATH.
+pub fn check_shim(app_name: &AppName, config: &Config) -> Result<(), ShimDataError> {
+    let path = shim_path(app_name, config);
+    if path.exists() && !is_shim(&path) {
+        return Err(ShimDataError::CommandExists(path));
+    }
+
+    match utils::find_in_path(&shim_name(app_name)) {
+        Some(existing) if existing != path && !is_shim(&existing) => {
+            Err(ShimDataError::CommandExists(existing))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn create_shim(app: &App, config: &Config) -> Result<(), ShimDataError> {
+    let name = AppName::parse(&app.name).map_err(ShimDataError::InvalidName)?;
+    check_shim(&name, config)?;
+
+    let content = format!(
+        "#!/bin/sh\n{}, do not edit.\nexec {} '{}' \"$@\"\n",
+        MARKER,
+        utils::shell_quote(config.launcher()),
+        name.slug()
+    );
+
+    std::fs::create_dir_all(&config.bin_path).map_err(ShimDataError::Io)?;
+    let path = shim_path(&name, config);
+    std::fs::write(&path, content).map_err(ShimDataError::Io)?;
+
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        .map_err(ShimDataError::Io)?;
+
+    Ok(())
+}
+
+// Removes the app's shim, leaving files we didn't generate alone.
+pub fn remove_shim(app_name: &AppName, config: &Config) -> Result<(), ShimDataError> {
+    let path = shim_path(app_name, config);
+    if !is_shim(&path) {
+        return Ok(());
+    }
+
+    std::fs::remove_file(path).map_err(ShimDataError::Io)
+}
+
+fn is_shim(path: &std::path::Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|content| content.contains(MARKER))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::create_test_config::create_test_config;
+
+    #[test]
+    fn test_create_shim() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let app = App {
+            name: "Google Mail".to_string(),
+            url: "https://mail.google.com".to_string(),
+            ..Default::default()
+        };
+
+        create_shim(&app, &config).unwrap();
+
+        let path = config.bin_path.join("google-mail");
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "#!/bin/sh\n# Generated by tarantula, do not edit.\nexec tarantula 'google_mail' \"$@\"\n"
+        );
+
+        // recreating our own shim is fine
+        create_shim(&app, &config).unwrap();
+
+        remove_shim(&AppName::parse(&app.name).unwrap(), &config).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_refuses_to_shadow_commands() {
+        let (config, _app_dir, _desktop_dir) = create_test_config();
+        let notes = AppName::parse("Notes").unwrap();
+
+        std::fs::create_dir_all(&config.bin_path).unwrap();
+        std::fs::write(config.bin_path.join("notes"), "#!/bin/sh\n").unwrap();
+        assert!(matches!(
+            check_shim(&notes, &config),
+            Err(ShimDataError::CommandExists(_))
+        ));
+
+        // sh is on $PATH on every system the tests run on
+        assert!(matches!(
+            check_shim(&AppName::parse("sh").unwrap(), &config),
+            Err(ShimDataError::CommandExists(_))
+        ));
+
+        remove_shim(&notes, &config).unwrap();
+        assert!(config.bin_path.join("notes").exists());
+    }
+}
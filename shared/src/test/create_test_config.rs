@@ -12,7 +12,14 @@ pub fn create_test_config() -> (config::Config, tempfile::TempDir, tempfile::Tem
         config::Config {
             app_data_path,
             desktop_data_path,
+            desktop_directory_path: app_dir.path().join("desktop-directories"),
             browser_path: None,
+            bin_path: app_dir.path().join("bin"),
+            xdg_config_path: app_dir.path().join("config"),
+            compositors: Vec::new(),
+            launcher_command: None,
+            command_template: Default::default(),
+            session_flags: Default::default(),
         },
         app_dir,
         desktop_dir,
@@ -1,10 +1,54 @@
 use regex::Regex;
+use std::path::{Path, PathBuf};
 
 pub fn is_url(s: &str) -> bool {
     let url_regex = Regex::new(r"^(http|https)://").unwrap();
     url_regex.is_match(s)
 }
 
+// Resolves a command the way the shell would, returning absolute paths unchanged.
+pub fn find_in_path(command: &str) -> Option<PathBuf> {
+    if command.contains('/') {
+        let path = Path::new(command);
+        return is_executable(path).then(|| path.to_path_buf());
+    }
+
+    find_in(command, &std::env::var_os("PATH")?)
+}
+
+// Like find_in_path, but searches the given $PATH value.
+pub fn find_in(command: &str, paths: &std::ffi::OsStr) -> Option<PathBuf> {
+    if command.contains('/') {
+        return find_in_path(command);
+    }
+
+    std::env::split_paths(paths)
+        .map(|dir| dir.join(command))
+        .find(|path| is_executable(path))
+}
+
+// Quotes an argument for sh, leaving plain words and paths as they are.
+pub fn shell_quote(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/._-+=:,@".contains(c));
+    if is_plain {
+        return arg.to_string();
+    }
+
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,4 +64,23 @@ mod tests {
         let not_url = "example.com";
         assert!(!is_url(not_url));
     }
+
+    #[test]
+    fn test_find_in_path() {
+        assert_eq!(find_in_path("/bin/sh"), Some(PathBuf::from("/bin/sh")));
+        assert!(find_in_path("sh").is_some());
+        assert!(find_in_path("tarantula-missing-command").is_none());
+        assert!(find_in("sh", std::ffi::OsStr::new("/tarantula-missing-dir")).is_none());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/usr/bin/tarantula"), "/usr/bin/tarantula");
+        assert_eq!(
+            shell_quote("/home/me/My Apps/tarantula"),
+            "'/home/me/My Apps/tarantula'"
+        );
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
 }
@@ -1,3 +1,19 @@
+// the app's commands, which the capabilities grant to the main window only
+const COMMANDS: &[&str] = &[
+    "get_app_data",
+    "run_app",
+    "install_app",
+    "uninstall_app",
+    "update_app",
+    "add_action",
+    "remove_action",
+    "set_autostart",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
 }
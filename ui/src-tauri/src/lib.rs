@@ -1,10 +1,16 @@
+use shared::app::action;
+use shared::app::autostart;
 use shared::app::config;
 use shared::app::install;
 use shared::app::run;
+use shared::app::run::embedded;
 use shared::app::uninstall;
 use shared::app::update;
-use shared::domain::app::App;
+use shared::domain::app::{App, Autostart, Geometry};
 use shared::infra::app_data;
+use std::path::PathBuf;
+use tauri::{WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_opener::OpenerExt;
 
 #[tauri::command]
 fn get_app_data() -> Vec<App> {
@@ -15,13 +21,23 @@ fn get_app_data() -> Vec<App> {
 #[tauri::command]
 fn run_app(app_name: String) {
     let config = config::create_config().unwrap();
-    run::run(&app_name, &config).unwrap();
+    let options = run::RunOptions {
+        // apps with the embedded engine open in another instance of this binary
+        ui_binary: Some(|| std::env::current_exe().ok()),
+        ..Default::default()
+    };
+    run::run(&app_name, &options, &config).unwrap();
 }
 
 #[tauri::command]
-fn install_app(name: String, url: String) -> Result<(), String> {
+fn install_app(
+    name: String,
+    url: String,
+    options: Option<install::InstallOptions>,
+) -> Result<(), String> {
     let config = config::create_config().unwrap();
-    install::install(&name, &url, &config).map_err(|e| format!("{:?}", e))
+    install::install(&name, &url, &options.unwrap_or_default(), &config)
+        .map_err(|e| format!("{:?}", e))
 }
 
 #[tauri::command]
@@ -36,17 +52,201 @@ fn update_app(name: String, options: update::UpdateOptions) -> Result<(), String
     update::update(&name, &options, &config).map_err(|e| format!("{:?}", e))
 }
 
+#[tauri::command]
+fn add_action(name: String, label: String, target: String) -> Result<(), String> {
+    let config = config::create_config().unwrap();
+    action::add(&name, &label, &target, &config).map_err(|e| format!("{:?}", e))
+}
+
+#[tauri::command]
+fn remove_action(name: String, label: String) -> Result<(), String> {
+    let config = config::create_config().unwrap();
+    action::remove(&name, &label, &config).map_err(|e| format!("{:?}", e))
+}
+
+#[tauri::command]
+fn set_autostart(name: String, autostart: Option<Autostart>) -> Result<(), String> {
+    let config = config::create_config().unwrap();
+    match autostart {
+        Some(autostart) => autostart::enable(&name, autostart, &config),
+        None => autostart::disable(&name, &config),
+    }
+    .map_err(|e| format!("{:?}", e))
+}
+
+// The commands the UI calls. Only the main window is granted them, see build.rs and
+// capabilities/default.json.
+fn invoke_handler<R: tauri::Runtime>() -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync {
+    tauri::generate_handler![
+        get_app_data,
+        run_app,
+        install_app,
+        uninstall_app,
+        update_app,
+        add_action,
+        remove_action,
+        set_autostart
+    ]
+}
+
+// Label of the single app mode's window, which no capability covers.
+const WEB_APP_WINDOW: &str = "web-app";
+
+// Single app mode the embedded engine starts the UI in:
+// ui --web-app <name> --url <url> --data-dir <dir>
+struct WebApp {
+    name: String,
+    url: tauri::Url,
+    data_dir: PathBuf,
+    geometry: Option<Geometry>,
+    scale: Option<f64>,
+    user_agent: Option<String>,
+}
+
+fn web_app_args(args: &[String]) -> Option<WebApp> {
+    let value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|i| args.get(i + 1))
+    };
+    Some(WebApp {
+        name: value("--web-app")?.clone(),
+        url: value("--url")?.parse().ok()?,
+        data_dir: PathBuf::from(value("--data-dir")?),
+        geometry: value("--geometry").and_then(|g| g.parse().ok()),
+        scale: value("--scale").and_then(|s| s.parse().ok()),
+        user_agent: value("--user-agent").cloned(),
+    })
+}
+
+// Opens the app in a window of its own. The capabilities only grant the commands
+// above to the main window, so the site can't call them.
+fn open_web_app(handle: &tauri::AppHandle, web_app: &WebApp) -> tauri::Result<()> {
+    let app = config::create_config()
+        .ok()
+        .and_then(|config| app_data::get_app(&web_app.name, &config).ok().flatten());
+
+    let (width, height) = web_app
+        .geometry
+        .map(|g| (g.width.into(), g.height.into()))
+        .unwrap_or((1200.0, 800.0));
+    let app_url = web_app.url.clone();
+    let opener = handle.clone();
+    let mut builder = WebviewWindowBuilder::new(
+        handle,
+        WEB_APP_WINDOW,
+        WebviewUrl::External(web_app.url.clone()),
+    )
+    .title(&web_app.name)
+    .inner_size(width, height)
+    .data_directory(web_app.data_dir.clone())
+    .on_navigation(move |target| {
+        if !embedded::is_external(&app_url, target) {
+            return true;
+        }
+        // links that leave the app open in the default browser
+        if let Err(e) = opener.opener().open_url(target.as_str(), None::<&str>) {
+            eprintln!("Failed to open {}: {}", target, e);
+        }
+        false
+    });
+    if let Some((x, y)) = web_app.geometry.and_then(|g| g.position) {
+        builder = builder.position(x.into(), y.into());
+    }
+    if let Some(user_agent) = &web_app.user_agent {
+        builder = builder.user_agent(user_agent);
+    }
+    if let Some(icon) = app.as_ref().and_then(|app| app.icon.as_ref()) {
+        match tauri::image::Image::from_path(icon) {
+            Ok(image) => builder = builder.icon(image)?,
+            Err(e) => eprintln!("Failed to load icon {}: {}", icon, e),
+        }
+    }
+    let window = builder.build()?;
+    if let Some(scale) = web_app.scale {
+        window.set_zoom(scale)?;
+    }
+    Ok(())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let web_app = web_app_args(&args);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![
-            get_app_data,
-            run_app,
-            install_app,
-            uninstall_app,
-            update_app
-        ])
+        .setup(move |app| {
+            match &web_app {
+                Some(web_app) => open_web_app(app.handle(), web_app)?,
+                // the main window isn't created from the config, as the single app mode has none
+                None => {
+                    WebviewWindowBuilder::from_config(app.handle(), &app.config().app.windows[0])?
+                        .build()?;
+                }
+            }
+            Ok(())
+        })
+        .invoke_handler(invoke_handler())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::ipc::{CallbackFn, InvokeBody};
+    use tauri::test::{get_ipc_response, mock_builder, INVOKE_KEY};
+    use tauri::webview::InvokeRequest;
+
+    fn invoke<W: AsRef<tauri::Webview<tauri::test::MockRuntime>>>(
+        webview: &W,
+        cmd: &str,
+    ) -> Result<(), String> {
+        let request = InvokeRequest {
+            cmd: cmd.to_string(),
+            callback: CallbackFn(0),
+            error: CallbackFn(1),
+            // the UI's own pages, which the main window's capability is for
+            url: "tauri://localhost".parse().unwrap(),
+            // no arguments, so commands the ACL lets through fail before they run
+            body: InvokeBody::default(),
+            headers: Default::default(),
+            invoke_key: INVOKE_KEY.to_string(),
+        };
+        get_ipc_response(webview, request)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_web_app_window_cant_invoke_commands() {
+        let app = mock_builder()
+            .invoke_handler(invoke_handler())
+            .build(tauri::generate_context!())
+            .unwrap();
+        let main = WebviewWindowBuilder::new(&app, "main", WebviewUrl::default())
+            .build()
+            .unwrap();
+        let web_app = WebviewWindowBuilder::new(&app, WEB_APP_WINDOW, WebviewUrl::default())
+            .build()
+            .unwrap();
+
+        let error = invoke(&main, "uninstall_app").unwrap_err();
+        assert!(!error.contains("not allowed"), "{}", error);
+
+        for cmd in [
+            "get_app_data",
+            "run_app",
+            "install_app",
+            "uninstall_app",
+            "update_app",
+            "add_action",
+            "remove_action",
+            "set_autostart",
+        ] {
+            let error = invoke(&web_app, cmd).unwrap_err();
+            assert!(error.contains("not allowed"), "{}: {}", cmd, error);
+        }
+    }
+}
@@ -13,8 +13,67 @@ type AppType = {
     name: string;
     url: string;
     icon?: string;
+    description?: string;
+    keywords?: string[];
+    categories?: string[];
+    localized_names?: {[locale: string]: string};
+    hidden?: boolean;
+    actions?: ActionType[];
+    autostart?: AutostartType;
 }
 
+type AutostartType = {
+    delay?: number;
+    minimized?: boolean;
+}
+
+type ActionType = {
+    label: string;
+    target: string;
+}
+
+type FormData = {
+    name: string;
+    url: string;
+    icon: string;
+    description: string;
+    keywords: string;
+    categories: string;
+    localizedNames: string;
+    hidden: boolean;
+}
+
+const emptyForm: FormData = {
+    name: "",
+    url: "",
+    icon: "",
+    description: "",
+    keywords: "",
+    categories: "",
+    localizedNames: "",
+    hidden: false,
+};
+
+const splitList = (value: string) => value.split(",").map((v) => v.trim()).filter((v) => v);
+
+// "de=Post, fr=Courrier" <-> { de: "Post", fr: "Courrier" }
+const parseLocalizedNames = (value: string) => splitList(value).reduce((names, pair) => {
+    const [locale, ...name] = pair.split("=");
+    if (!locale || !name.length) return names;
+    return { ...names, [locale.trim()]: name.join("=").trim() };
+}, {} as {[locale: string]: string});
+
+const formatLocalizedNames = (names?: {[locale: string]: string}) =>
+    Object.entries(names || {}).map(([locale, name]) => `${locale}=${name}`).join(", ");
+
+const entryOptions = (formData: FormData) => ({
+    description: formData.description,
+    keywords: splitList(formData.keywords),
+    categories: splitList(formData.categories),
+    localized_names: parseLocalizedNames(formData.localizedNames),
+    hidden: formData.hidden,
+});
+
 function App() {
     const [apps, setApps] = React.useState<AppType[]>([]);
     const [imageSrcs, setImageSrc] = React.useState<{[key: string]: string}>({})
@@ -22,20 +81,26 @@ function App() {
     const [editOpen, setEditOpen] = React.useState(false);
     const [deleteOpen, setDeleteOpen] = React.useState(false);
     const [selectedApp, setSelectedApp] = React.useState<AppType | null>(null);
-    const [formData, setFormData] = React.useState({ name: "", url: "", icon: "" });
+    const [formData, setFormData] = React.useState<FormData>(emptyForm);
+    const [newAction, setNewAction] = React.useState<ActionType>({ label: "", target: "" });
  
     React.useEffect(() => {
         console.log('formDta changed:', formData);
     }, [formData])
 
-    React.useEffect(() => {
+    const loadApps = () => {
         invoke("get_app_data").then((data) => {
             console.log(data);
-            setApps(data as AppType[]);
+            const apps = data as AppType[];
+            setApps(apps);
+            // keep the app open in the edit dialog in sync
+            setSelectedApp((selected) => apps.find((app) => app.name === selected?.name) || null);
         }).catch((err) => {
             console.error(err);
         });
-    }, [])
+    }
+
+    React.useEffect(loadApps, [])
 
     React.useEffect(() => {
         const sources = apps.reduce((prev, app) => {
@@ -54,14 +119,23 @@ function App() {
     }
 
     const handleCreateOpen = () => {
-        setFormData({ name: "", url: "", icon: "" });
+        setFormData(emptyForm);
         setCreateOpen(true);
     };
 
     const handleEditOpen = (app: AppType) => {
         setSelectedApp(app);
         setEditOpen(true);
-        setFormData({ name: app.name, url: app.url, icon: app.icon || "" });
+        setFormData({
+            name: app.name,
+            url: app.url,
+            icon: app.icon || "",
+            description: app.description || "",
+            keywords: (app.keywords || []).join(", "),
+            categories: (app.categories || []).join(", "),
+            localizedNames: formatLocalizedNames(app.localized_names),
+            hidden: app.hidden || false,
+        });
     };
 
     const handleDeleteOpen = (app: AppType) => {
@@ -70,15 +144,52 @@ function App() {
     };
 
     const handleCreate = () => {
-        console.log("Create app:", formData);
+        const { description, ...options } = entryOptions(formData);
+        invoke("install_app", {
+            name: formData.name,
+            url: formData.url,
+            options: { ...options, description: description || null },
+        }).then(loadApps).catch((err) => {
+            console.error(err);
+        });
         setCreateOpen(false);
     };
 
     const handleEdit = () => {
-        console.log("Edit app:", formData);
+        if (!selectedApp) return;
+        invoke("update_app", {
+            name: selectedApp.name,
+            options: { name: formData.name, url: formData.url, ...entryOptions(formData) },
+        }).then(loadApps).catch((err) => {
+            console.error(err);
+        });
         setEditOpen(false);
     };
 
+    const handleAddAction = () => {
+        if (!selectedApp) return;
+        invoke("add_action", { name: selectedApp.name, ...newAction }).then(() => {
+            setNewAction({ label: "", target: "" });
+            loadApps();
+        }).catch((err) => {
+            console.error(err);
+        });
+    };
+
+    const handleRemoveAction = (action: ActionType) => {
+        if (!selectedApp) return;
+        invoke("remove_action", { name: selectedApp.name, label: action.label }).then(loadApps).catch((err) => {
+            console.error(err);
+        });
+    };
+
+    const handleAutostart = (autostart: AutostartType | null) => {
+        if (!selectedApp) return;
+        invoke("set_autostart", { name: selectedApp.name, autostart }).then(loadApps).catch((err) => {
+            console.error(err);
+        });
+    };
+
     const handleDelete = () => {
         console.log("Delete app:", selectedApp?.name);
         setDeleteOpen(false);
@@ -121,6 +232,24 @@ function App() {
                     placeholder="https://example.com"
                   />
                 </div>
+                <div className="grid gap-2">
+                  <Label htmlFor="create-description">Description (optional)</Label>
+                  <Input
+                    id="create-description"
+                    value={formData.description}
+                    onChange={(e) => setFormData({ ...formData, description: e.target.value })}
+                    placeholder="What the app is for"
+                  />
+                </div>
+                <div className="grid gap-2">
+                  <Label htmlFor="create-keywords">Keywords (optional)</Label>
+                  <Input
+                    id="create-keywords"
+                    value={formData.keywords}
+                    onChange={(e) => setFormData({ ...formData, keywords: e.target.value })}
+                    placeholder="email, inbox"
+                  />
+                </div>
               </div>
               <DialogFooter>
                 <Button variant="outline" onClick={() => setCreateOpen(false)}>
@@ -202,6 +331,109 @@ function App() {
                   placeholder="https://example.com"
                 />
               </div>
+              <div className="grid gap-2">
+                <Label htmlFor="edit-description">Description</Label>
+                <Input
+                  id="edit-description"
+                  value={formData.description}
+                  onChange={(e) => setFormData({ ...formData, description: e.target.value })}
+                  placeholder="What the app is for"
+                />
+              </div>
+              <div className="grid gap-2">
+                <Label htmlFor="edit-keywords">Keywords</Label>
+                <Input
+                  id="edit-keywords"
+                  value={formData.keywords}
+                  onChange={(e) => setFormData({ ...formData, keywords: e.target.value })}
+                  placeholder="email, inbox"
+                />
+              </div>
+              <div className="grid gap-2">
+                <Label htmlFor="edit-categories">Categories</Label>
+                <Input
+                  id="edit-categories"
+                  value={formData.categories}
+                  onChange={(e) => setFormData({ ...formData, categories: e.target.value })}
+                  placeholder="Network, Email"
+                />
+              </div>
+              <div className="grid gap-2">
+                <Label htmlFor="edit-localized-names">Translated names</Label>
+                <Input
+                  id="edit-localized-names"
+                  value={formData.localizedNames}
+                  onChange={(e) => setFormData({ ...formData, localizedNames: e.target.value })}
+                  placeholder="de=Post, fr=Courrier"
+                />
+              </div>
+              <div className="flex items-center gap-2">
+                <input
+                  id="edit-hidden"
+                  type="checkbox"
+                  checked={formData.hidden}
+                  onChange={(e) => setFormData({ ...formData, hidden: e.target.checked })}
+                />
+                <Label htmlFor="edit-hidden">Hide from launcher menus</Label>
+              </div>
+              <div className="flex items-center gap-2">
+                <input
+                  id="edit-autostart"
+                  type="checkbox"
+                  checked={!!selectedApp?.autostart}
+                  onChange={(e) => handleAutostart(e.target.checked ? {} : null)}
+                />
+                <Label htmlFor="edit-autostart">Start at login</Label>
+                {selectedApp?.autostart && (
+                  <>
+                    <Input
+                      className="w-20"
+                      type="number"
+                      min={0}
+                      value={selectedApp.autostart.delay ?? ""}
+                      onChange={(e) => handleAutostart({
+                        ...selectedApp.autostart,
+                        delay: e.target.value ? Number(e.target.value) : undefined,
+                      })}
+                      placeholder="Delay"
+                    />
+                    <input
+                      id="edit-autostart-minimized"
+                      type="checkbox"
+                      checked={selectedApp.autostart.minimized || false}
+                      onChange={(e) => handleAutostart({ ...selectedApp.autostart, minimized: e.target.checked })}
+                    />
+                    <Label htmlFor="edit-autostart-minimized">Minimized</Label>
+                  </>
+                )}
+              </div>
+              <div className="grid gap-2">
+                <Label>Quick links</Label>
+                {selectedApp?.actions?.map((action) => (
+                  <div key={action.label} className="flex items-center gap-2">
+                    <span className="flex-1 truncate">{action.label}</span>
+                    <span className="flex-1 truncate text-sm text-gray-500">{action.target}</span>
+                    <Button variant="outline" size="sm" onClick={() => handleRemoveAction(action)}>
+                      <Trash2 className="h-4 w-4" />
+                    </Button>
+                  </div>
+                ))}
+                <div className="flex gap-2">
+                  <Input
+                    value={newAction.label}
+                    onChange={(e) => setNewAction({ ...newAction, label: e.target.value })}
+                    placeholder="My PRs"
+                  />
+                  <Input
+                    value={newAction.target}
+                    onChange={(e) => setNewAction({ ...newAction, target: e.target.value })}
+                    placeholder="/pulls"
+                  />
+                  <Button variant="outline" size="sm" onClick={handleAddAction}>
+                    <Plus className="h-4 w-4" />
+                  </Button>
+                </div>
+              </div>
               <div className="grid gap-2">
                 <Label htmlFor="edit-icon">Icon (optional)</Label>
                 <input